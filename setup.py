@@ -53,6 +53,11 @@ setup(
         'Topic :: Utilities',
     ],
     install_requires=[
-        'networkx>=2.1,<3',
+        'networkx>=2.4,<3',
     ],
+    entry_points={
+        'console_scripts': [
+            'grimp = grimp.cli:main',
+        ],
+    },
 )
@@ -1,4 +1,5 @@
 __version__ = "1.2.3 "
 
 from .domain.valueobjects import Module, DirectImport  # noqa: F401
-from .main import build_graph  # noqa: F401
+from .adaptors.graph import ImportGraph  # noqa: F401
+from .main import build_graph, build_graph_from_sources, discover_packages  # noqa: F401
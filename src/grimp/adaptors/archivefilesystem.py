@@ -0,0 +1,170 @@
+import io
+import os
+import time
+import tokenize
+import zipfile
+from typing import Dict, Iterator, List, Optional, Set, Tuple
+
+from .filesystem import FileSystem
+
+ARCHIVE_EXTENSIONS = (".zip", ".whl", ".egg")
+
+
+class _ArchiveIndex:
+    """
+    The directory structure of a zip archive, keyed by the path of each directory within it
+    ('' for the root of the archive).
+    """
+
+    def __init__(self, zip_file: zipfile.ZipFile, signature: Tuple[int, int]) -> None:
+        self.zip_file = zip_file
+        # The modification time and size of the archive when it was read, to tell whether it has
+        # changed since.
+        self.signature = signature
+        self.subdirectories: Dict[str, List[str]] = {"": []}
+        self.files: Dict[str, List[str]] = {"": []}
+        self.file_paths: Set[str] = set()
+
+        for name in zip_file.namelist():
+            is_directory = name.endswith("/")
+            components = name.rstrip("/").split("/")
+            parent = ""
+            # Register each ancestor directory of the entry (and the entry itself, if it is
+            # a directory), as archives don't necessarily contain entries for directories.
+            directory_components = components if is_directory else components[:-1]
+            for component in directory_components:
+                path = f"{parent}/{component}" if parent else component
+                if path not in self.subdirectories:
+                    self.subdirectories[path] = []
+                    self.files[path] = []
+                    self.subdirectories[parent].append(component)
+                parent = path
+            if not is_directory:
+                self.files[parent].append(components[-1])
+                self.file_paths.add(name)
+
+
+class ArchiveFileSystem(FileSystem):
+    """
+    File system that can also read the contents of zip archives (including wheels and eggs).
+
+    Paths inside an archive are written as if the archive were a directory, for example
+    '/path/to/mydistribution.whl/mypackage/__init__.py'. This is the form used by Python's
+    zipimport machinery, so packages imported from archives can be analysed without first
+    unpacking them.
+    """
+
+    def __init__(self) -> None:
+        self._archive_indexes: Dict[str, _ArchiveIndex] = {}
+
+    def walk(self, directory_name, follow_symlinks: bool = False):
+        archive_path = self._split_archive_path(directory_name)
+        if archive_path is None:
+            yield from super().walk(directory_name, follow_symlinks=follow_symlinks)
+            return
+
+        archive_filename, inner_directory = archive_path
+        index = self._get_archive_index(archive_filename)
+        if inner_directory not in index.subdirectories:
+            return
+        yield from self._walk_archive(index, directory_name, inner_directory)
+
+    def realpath(self, path: str) -> str:
+        archive_path = self._split_archive_path(path)
+        if archive_path is None:
+            return super().realpath(path)
+        archive_filename, inner_path = archive_path
+        return self._join_archive_path(super().realpath(archive_filename), inner_path)
+
+    def read(self, file_name: str) -> str:
+        archive_path = self._split_archive_path(file_name)
+        if archive_path is None:
+            return super().read(file_name)
+
+        archive_filename, inner_path = archive_path
+        index = self._get_archive_index(archive_filename)
+        if inner_path not in index.file_paths:
+            raise FileNotFoundError(file_name)
+        contents = index.zip_file.read(inner_path)
+        # Decode the contents in the same way as tokenize.open.
+        encoding, _ = tokenize.detect_encoding(io.BytesIO(contents).readline)
+        return io.TextIOWrapper(io.BytesIO(contents), encoding, line_buffering=True).read()
+
+    def getsize(self, file_name: str) -> int:
+        archive_path = self._split_archive_path(file_name)
+        if archive_path is None:
+            return super().getsize(file_name)
+
+        archive_filename, inner_path = archive_path
+        index = self._get_archive_index(archive_filename)
+        if inner_path not in index.file_paths:
+            raise FileNotFoundError(file_name)
+        return index.zip_file.getinfo(inner_path).file_size
+
+    def getmtime(self, file_name: str) -> float:
+        archive_path = self._split_archive_path(file_name)
+        if archive_path is None:
+            return super().getmtime(file_name)
+
+        archive_filename, inner_path = archive_path
+        index = self._get_archive_index(archive_filename)
+        if inner_path not in index.file_paths:
+            raise FileNotFoundError(file_name)
+        # Zip archives record the local time at which each file was modified.
+        date_time = index.zip_file.getinfo(inner_path).date_time
+        return time.mktime(date_time + (0, 0, -1))
+
+    def exists(self, file_name: str) -> bool:
+        archive_path = self._split_archive_path(file_name)
+        if archive_path is None:
+            return super().exists(file_name)
+        archive_filename, inner_path = archive_path
+        return inner_path in self._get_archive_index(archive_filename).file_paths
+
+    def _walk_archive(
+        self, index: _ArchiveIndex, dirpath: str, inner_directory: str
+    ) -> Iterator[Tuple[str, List[str], List[str]]]:
+        dirnames = list(index.subdirectories[inner_directory])
+        yield dirpath, dirnames, list(index.files[inner_directory])
+        # Like os.walk, only descend into the directories that remain in dirnames, in case the
+        # caller has modified it in place.
+        for dirname in dirnames:
+            yield from self._walk_archive(
+                index,
+                self.join(dirpath, dirname),
+                f"{inner_directory}/{dirname}" if inner_directory else dirname,
+            )
+
+    def _split_archive_path(self, path: str) -> Optional[Tuple[str, str]]:
+        """
+        If the path is within an archive, return the filename of the archive and the path within
+        it (using forward slashes, as in the archive itself). Otherwise, return None.
+        """
+        head = path
+        inner_components: List[str] = []
+        while True:
+            if head.lower().endswith(ARCHIVE_EXTENSIONS) and os.path.isfile(head):
+                return head, "/".join(reversed(inner_components))
+            new_head, component = self.split(head)
+            if new_head == head:
+                return None
+            if component:
+                inner_components.append(component)
+            head = new_head
+
+    def _join_archive_path(self, archive_filename: str, inner_path: str) -> str:
+        if not inner_path:
+            return archive_filename
+        return self.join(archive_filename, *inner_path.split("/"))
+
+    def _get_archive_index(self, archive_filename: str) -> _ArchiveIndex:
+        stat_result = os.stat(archive_filename)
+        signature = (stat_result.st_mtime_ns, stat_result.st_size)
+        index = self._archive_indexes.get(archive_filename)
+        if index is None or index.signature != signature:
+            # The archive is new, or has changed (e.g. a wheel has been rebuilt) since it was read.
+            if index is not None:
+                index.zip_file.close()
+            index = _ArchiveIndex(zipfile.ZipFile(archive_filename), signature)
+            self._archive_indexes[archive_filename] = index
+        return index
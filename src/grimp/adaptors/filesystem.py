@@ -13,8 +13,8 @@ class FileSystem(AbstractFileSystem):
     def dirname(self, filename: str) -> str:
         return os.path.dirname(filename)
 
-    def walk(self, directory_name):
-        yield from os.walk(directory_name)
+    def walk(self, directory_name, follow_symlinks: bool = False):
+        yield from os.walk(directory_name, followlinks=follow_symlinks)
 
     def join(self, *components: str) -> str:
         return os.path.join(*components)
@@ -22,11 +22,20 @@ class FileSystem(AbstractFileSystem):
     def split(self, file_name: str) -> Tuple[str, str]:
         return os.path.split(file_name)
 
+    def realpath(self, path: str) -> str:
+        return os.path.realpath(path)
+
     def read(self, file_name: str) -> str:
         # Use tokenize.open to give us a better chance of successfully decoding
         # source code in a non-ascii compatible encoding.
         with tokenize.open(file_name) as file:
             return file.read()
 
+    def getsize(self, file_name: str) -> int:
+        return os.path.getsize(file_name)
+
+    def getmtime(self, file_name: str) -> float:
+        return os.path.getmtime(file_name)
+
     def exists(self, file_name: str) -> bool:
         return os.path.isfile(file_name)
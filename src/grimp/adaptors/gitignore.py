@@ -0,0 +1,144 @@
+import os
+import re
+from typing import List, NamedTuple, Optional, Pattern
+
+
+class _Rule(NamedTuple):
+    base_directory: str
+    regex: Pattern
+    is_negated: bool
+    is_anchored: bool
+    is_directory_only: bool
+
+
+class GitIgnoreRules:
+    """
+    A set of rules read from .gitignore files, used to determine whether a path is ignored.
+
+    Supports the commonly used parts of the gitignore pattern format: comments, negation with
+    '!', directory-only patterns ending in '/', patterns anchored by a '/', and the '*', '?',
+    '**' and '[...]' wildcards. See https://git-scm.com/docs/gitignore.
+    """
+
+    def __init__(self) -> None:
+        self._rules: List[_Rule] = []
+
+    def add_rules(self, base_directory: str, contents: str) -> None:
+        """
+        Add the rules from the contents of a .gitignore file.
+
+        Files should be added from the outermost directory inwards, as later rules take
+        precedence over earlier ones.
+
+        Args:
+            - base_directory: The directory containing the .gitignore file.
+            - contents:       The contents of the .gitignore file.
+        """
+        for line in contents.splitlines():
+            rule = self._parse_line(base_directory, line)
+            if rule:
+                self._rules.append(rule)
+
+    def is_ignored(self, path: str, is_directory: bool) -> bool:
+        """
+        Return whether the supplied path is ignored by the rules.
+
+        Note: this doesn't check whether any of the path's parent directories are ignored.
+        """
+        is_ignored = False
+        for rule in self._rules:
+            if rule.is_negated != is_ignored:
+                # The rule can't change the outcome.
+                continue
+            if self._rule_matches(rule, path, is_directory):
+                is_ignored = not rule.is_negated
+        return is_ignored
+
+    def _parse_line(self, base_directory: str, line: str) -> Optional[_Rule]:
+        pattern = line.rstrip()
+        if not pattern or pattern.startswith("#"):
+            return None
+
+        is_negated = pattern.startswith("!")
+        if is_negated:
+            pattern = pattern[1:]
+        if pattern.startswith("\\"):
+            # Escaped leading '#' or '!'.
+            pattern = pattern[1:]
+
+        is_directory_only = pattern.endswith("/")
+        pattern = pattern.rstrip("/")
+        # A pattern containing a slash (other than at the end) is relative to the directory
+        # containing the .gitignore. Otherwise, it may match at any level below it.
+        is_anchored = "/" in pattern
+        pattern = pattern.lstrip("/")
+        if not pattern:
+            return None
+
+        return _Rule(
+            # Strip any trailing separator, so we can treat the root directory like any other.
+            base_directory=base_directory.rstrip("/" + os.sep),
+            regex=re.compile(_translate(pattern)),
+            is_negated=is_negated,
+            is_anchored=is_anchored,
+            is_directory_only=is_directory_only,
+        )
+
+    def _rule_matches(self, rule: _Rule, path: str, is_directory: bool) -> bool:
+        if rule.is_directory_only and not is_directory:
+            return False
+
+        base_length = len(rule.base_directory)
+        is_within_base_directory = path.startswith(rule.base_directory) and path[
+            base_length : base_length + 1
+        ] in ("/", os.sep)
+        if not is_within_base_directory:
+            return False
+
+        relative_path = path[base_length + 1 :].replace(os.sep, "/")
+        if rule.is_anchored:
+            candidate = relative_path
+        else:
+            candidate = relative_path.split("/")[-1]
+        return bool(rule.regex.fullmatch(candidate))
+
+
+def _translate(pattern: str) -> str:
+    """
+    Translate a gitignore pattern into a regular expression.
+    """
+    regex = ""
+    index = 0
+    length = len(pattern)
+    while index < length:
+        if pattern.startswith("**/", index):
+            # Zero or more directories.
+            regex += "(?:.*/)?"
+            index += 3
+        elif pattern.startswith("/**", index) and index + 3 == length:
+            # Everything inside.
+            regex += "/.*"
+            index += 3
+        elif pattern.startswith("**", index):
+            regex += ".*"
+            index += 2
+        else:
+            character = pattern[index]
+            index += 1
+            if character == "*":
+                regex += "[^/]*"
+            elif character == "?":
+                regex += "[^/]"
+            elif character == "[":
+                closing_index = pattern.find("]", index + 1)
+                if closing_index == -1:
+                    regex += re.escape(character)
+                else:
+                    character_class = pattern[index:closing_index]
+                    if character_class.startswith("!"):
+                        character_class = "^" + character_class[1:]
+                    regex += f"[{character_class}]"
+                    index = closing_index + 1
+            else:
+                regex += re.escape(character)
+    return regex
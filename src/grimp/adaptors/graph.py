@@ -1,10 +1,115 @@
-from typing import Any, Dict, Iterator, List, Optional, Set, Tuple, Union
+import ast
+import copy
+import csv
+import functools
+import html
+import io
+import json
+import logging
+import os
+import sqlite3
+import time
+import types
+from typing import Any, Dict, Iterable, Iterator, List, Optional, Set, TextIO, Tuple, Union
 import networkx  # type: ignore
 import networkx.algorithms  # type: ignore
+from grimp.adaptors import htmlviewer
 from grimp.application.ports import graph
-from grimp.domain.valueobjects import Module
+from grimp.domain.valueobjects import ExportFormat, ExternalModuleKind, ImportScope, Module
 from grimp.exceptions import ModuleNotPresent
-from grimp.helpers import wrap_generator
+from grimp.helpers import estimate_size, wrap_generator
+
+logger = logging.getLogger(__name__)
+
+# The columns of the CSV files written by ImportGraph.to_csv.
+CSV_FIELDNAMES = ("importer", "imported", "line_number", "line_contents")
+
+# The tables written by ImportGraph.to_sqlite.
+SQLITE_SCHEMA = """
+DROP TABLE IF EXISTS modules;
+DROP TABLE IF EXISTS imports;
+DROP TABLE IF EXISTS import_details;
+CREATE TABLE modules (
+    name TEXT PRIMARY KEY,
+    is_squashed BOOLEAN NOT NULL,
+    external_kind TEXT,
+    package_name TEXT,
+    filename TEXT,
+    mtime REAL
+);
+CREATE TABLE imports (
+    importer TEXT NOT NULL REFERENCES modules (name),
+    imported TEXT NOT NULL REFERENCES modules (name),
+    PRIMARY KEY (importer, imported)
+);
+CREATE TABLE import_details (
+    importer TEXT NOT NULL,
+    imported TEXT NOT NULL,
+    line_number INTEGER NOT NULL,
+    line_contents TEXT NOT NULL,
+    is_dynamic BOOLEAN NOT NULL,
+    scope TEXT NOT NULL,
+    imported_object TEXT,
+    is_type_checking BOOLEAN NOT NULL,
+    ignored_for TEXT NOT NULL,
+    is_cimport BOOLEAN NOT NULL,
+    "column" INTEGER,
+    end_line_number INTEGER,
+    platform_condition TEXT,
+    is_optional BOOLEAN NOT NULL,
+    is_lazy BOOLEAN NOT NULL,
+    version_condition TEXT,
+    FOREIGN KEY (importer, imported) REFERENCES imports (importer, imported)
+);
+"""
+SQLITE_IMPORT_DETAILS_FIELDS = (
+    "importer",
+    "imported",
+    "line_number",
+    "line_contents",
+    "is_dynamic",
+    "scope",
+    "imported_object",
+    "is_type_checking",
+    "ignored_for",
+    "is_cimport",
+    "column",
+    "end_line_number",
+    "platform_condition",
+    "is_optional",
+    "is_lazy",
+    "version_condition",
+)
+
+
+def _query(method):
+    """
+    Decorate an ImportGraph query so that its duration is logged and, if the graph's query cache
+    is enabled, its results are remembered until the graph changes.
+    """
+
+    @functools.wraps(method)
+    def wrapper(self, *args, **kwargs):
+        start = time.perf_counter()
+        if self._query_cache is None:
+            result = method(self, *args, **kwargs)
+        else:
+            key = (method.__name__, args, tuple(sorted(kwargs.items())))
+            if key not in self._query_cache:
+                self._query_cache[key] = method(self, *args, **kwargs)
+            # Copy the result, so callers can't change what's in the cache.
+            result = copy.copy(self._query_cache[key])
+        if logger.isEnabledFor(logging.DEBUG):
+            arguments = ", ".join(
+                [repr(arg) for arg in args]
+                + [f"{name}={value!r}" for name, value in kwargs.items()]
+            )
+            logger.debug(
+                f"{method.__name__}({arguments}) took {time.perf_counter() - start:.3f}s."
+            )
+        return result
+
+    return wrapper
 
 
 class ImportGraph(graph.AbstractImportGraph):
@@ -17,6 +122,21 @@ class ImportGraph(graph.AbstractImportGraph):
         # Instantiate a dict that stores the details for all direct imports.
         self._import_details: Dict[str, List[Dict[str, Any]]] = {}
         self._squashed_modules: Set[str] = set()
+        self._external_module_kinds: Dict[str, str] = {}
+        self._build_info: Optional[Dict[str, Any]] = None
+        self._module_provenances: Dict[str, Dict[str, Any]] = {}
+        self._runtime_imports: Set[Tuple[str, str]] = set()
+        # The descendants of each module that find_descendants has been called for, discarded
+        # whenever a module is added or removed.
+        self._descendants_cache: Dict[str, Set[str]] = {}
+        # An index of which modules can reach which, built when needed and discarded whenever
+        # the graph changes (see _get_reachability_index).
+        self._condensation: Optional[networkx.DiGraph] = None
+        self._reachable_components: Dict[int, Set[int]] = {}
+        self._reaching_components: Dict[int, Set[int]] = {}
+        # The results of queries, keyed by method and arguments, if enabled using
+        # enable_query_cache. Discarded whenever the graph changes.
+        self._query_cache: Optional[Dict[Tuple[Any, ...], Any]] = None
 
     # Mechanics
     # ---------
@@ -29,7 +149,12 @@ class ImportGraph(graph.AbstractImportGraph):
             self._modules = set(self._networkx_graph.nodes)
         return self._modules
 
-    def add_module(self, module: str, is_squashed: bool = False) -> None:
+    def add_module(
+        self, module: str, is_squashed: bool = False, external_kind: Optional[str] = None
+    ) -> None:
+        if external_kind not in (None, ExternalModuleKind.STDLIB, ExternalModuleKind.THIRD_PARTY):
+            raise ValueError(f"Unknown external module kind {external_kind}.")
+
         ancestor_squashed_module = self._find_ancestor_squashed_module(module)
         if ancestor_squashed_module:
             raise ValueError(
@@ -44,15 +169,30 @@ class ImportGraph(graph.AbstractImportGraph):
                 )
 
         self._networkx_graph.add_node(module)
-        self._modules.add(module)
+        self._invalidate_indexes()
+        if module not in self._modules:
+            self._modules.add(module)
+            self._descendants_cache.clear()
 
         if is_squashed:
             self._mark_module_as_squashed(module)
+        if external_kind:
+            self._external_module_kinds[module] = external_kind
 
     def remove_module(self, module: str) -> None:
         if module in self.modules:
             self._networkx_graph.remove_node(module)
+            self._invalidate_indexes()
             self._modules.remove(module)
+            self._descendants_cache.clear()
+            self._external_module_kinds.pop(module, None)
+            self._module_provenances.pop(module, None)
+        # Otherwise merging the runtime imports would add the module back.
+        self._runtime_imports = {
+            (importer, imported)
+            for importer, imported in self._runtime_imports
+            if module not in (importer, imported)
+        }
 
     def squash_module(self, module: str) -> None:
         if self.is_module_squashed(module):
@@ -68,6 +208,14 @@ class ImportGraph(graph.AbstractImportGraph):
             for importing_module in self.find_modules_that_directly_import(descendant):
                 self.add_import(importer=importing_module, imported=squashed_root)
 
+        # Likewise for any runtime imports.
+        descendant_set = set(descendants)
+        for importer, imported in list(self._runtime_imports):
+            squashed_importer = squashed_root if importer in descendant_set else importer
+            squashed_imported = squashed_root if imported in descendant_set else imported
+            if squashed_importer != squashed_imported:
+                self._runtime_imports.add((squashed_importer, squashed_imported))
+
         # Now we've added imports to/from the root, we can delete the root's descendants.
         for descendant in descendants:
             self.remove_module(descendant)
@@ -87,6 +235,18 @@ class ImportGraph(graph.AbstractImportGraph):
         imported: str,
         line_number: Optional[int] = None,
         line_contents: Optional[str] = None,
+        is_dynamic: bool = False,
+        scope: str = ImportScope.MODULE,
+        imported_object: Optional[str] = None,
+        is_type_checking: bool = False,
+        ignored_for: Tuple[str, ...] = (),
+        is_cimport: bool = False,
+        column: Optional[int] = None,
+        end_line_number: Optional[int] = None,
+        platform_condition: Optional[str] = None,
+        is_optional: bool = False,
+        is_lazy: bool = False,
+        version_condition: Optional[str] = None,
     ) -> None:
         if any((line_number, line_contents)):
             if not all((line_number, line_contents)):
@@ -100,20 +260,86 @@ class ImportGraph(graph.AbstractImportGraph):
                     "imported": imported,
                     "line_number": line_number,
                     "line_contents": line_contents,
+                    "is_dynamic": is_dynamic,
+                    "scope": scope,
+                    "imported_object": imported_object,
+                    "is_type_checking": is_type_checking,
+                    "ignored_for": ignored_for,
+                    "is_cimport": is_cimport,
+                    "column": column,
+                    "end_line_number": end_line_number,
+                    "platform_condition": platform_condition,
+                    "is_optional": is_optional,
+                    "is_lazy": is_lazy,
+                    "version_condition": version_condition,
                 }
             )
 
         self._networkx_graph.add_edge(importer, imported)
+        self._invalidate_indexes()
         for module in (importer, imported):
             if module not in self.modules:
                 self.add_module(module)
 
     def remove_import(self, *, importer: str, imported: str) -> None:
         self._networkx_graph.remove_edge(importer, imported)
+        self._invalidate_indexes()
 
     def count_imports(self) -> int:
         return len(self._networkx_graph.edges)
 
+    def memory_usage(self) -> Dict[str, int]:
+        seen: Set[int] = set()
+        usage = {
+            "modules_and_imports": (
+                estimate_size(self._networkx_graph, seen) + estimate_size(self.modules, seen)
+            ),
+            "import_details": estimate_size(self._import_details, seen),
+            "metadata": sum(
+                estimate_size(metadata, seen)
+                for metadata in (
+                    self._squashed_modules,
+                    self._external_module_kinds,
+                    self._build_info,
+                    self._module_provenances,
+                    self._runtime_imports,
+                )
+            ),
+        }
+        usage["total"] = sum(usage.values())
+        return usage
+
+    def enable_query_cache(self) -> None:
+        if self._query_cache is None:
+            self._query_cache = {}
+
+    def disable_query_cache(self) -> None:
+        self._query_cache = None
+
+    def set_build_info(self, build_info: Dict[str, Any]) -> None:
+        self._build_info = copy.deepcopy(build_info)
+
+    def build_info(self) -> Optional[Dict[str, Any]]:
+        # Return a copy so the recorded metadata can't be altered by the caller.
+        return copy.deepcopy(self._build_info)
+
+    def set_module_provenance(
+        self, module: str, *, package_name: str, filename: str, mtime: float
+    ) -> None:
+        if module not in self.modules:
+            raise ModuleNotPresent(f'"{module}" not present in the graph.')
+        self._module_provenances[module] = {
+            "package_name": package_name,
+            "filename": filename,
+            "mtime": mtime,
+        }
+
+    def get_module_provenance(self, module: str) -> Optional[Dict[str, Any]]:
+        if module not in self.modules:
+            raise ModuleNotPresent(f'"{module}" not present in the graph.')
+        provenance = self._module_provenances.get(module)
+        return dict(provenance) if provenance else None
+
     # Descendants
     # -----------
 
@@ -135,11 +361,21 @@ class ImportGraph(graph.AbstractImportGraph):
         if self.is_module_squashed(module):
             raise ValueError("Cannot find descendants of a squashed module.")
 
-        descendants = set()
-        for potential_descendant in self.modules:
-            if Module(potential_descendant).is_descendant_of(Module(module)):
-                descendants.add(potential_descendant)
-        return descendants
+        if module not in self._descendants_cache:
+            self._descendants_cache[module] = {
+                potential_descendant
+                for potential_descendant in self.modules
+                if Module(potential_descendant).is_descendant_of(Module(module))
+            }
+        # Return a copy so the cache can't be altered by the caller.
+        return set(self._descendants_cache[module])
+
+    def find_external_modules(self, kind: Optional[str] = None) -> Set[str]:
+        return {
+            module
+            for module, external_kind in self._external_module_kinds.items()
+            if kind is None or external_kind == kind
+        }
 
     # Direct imports
     # --------------
@@ -180,7 +416,7 @@ class ImportGraph(graph.AbstractImportGraph):
 
     def get_import_details(
         self, *, importer: str, imported: str
-    ) -> List[Dict[str, Union[str, int]]]:
+    ) -> List[Dict[str, Union[str, int, bool, Tuple[str, ...], None]]]:
         import_details_for_importer = self._import_details.get(importer, [])
         # Only include the details for the imported module.
         return [i for i in import_details_for_importer if i["imported"] == imported]
@@ -188,58 +424,31 @@ class ImportGraph(graph.AbstractImportGraph):
     # Indirect imports
     # ----------------
 
+    @_query
     def find_downstream_modules(
         self, module: str, as_package: bool = False
     ) -> Set[str]:
-        # TODO optimise for as_package.
         if as_package:
             source_modules = self._all_modules_in_package(module)
         else:
             source_modules = {module}
+        return self._find_connected_modules(source_modules, downstream=True)
 
-        downstream_modules = set()
-
-        for candidate in filter(lambda m: m not in source_modules, self.modules):
-            for source_module in source_modules:
-                if networkx.algorithms.has_path(
-                    self._networkx_graph, candidate, source_module
-                ):
-                    downstream_modules.add(candidate)
-                    break
-
-        return downstream_modules
-
+    @_query
     def find_upstream_modules(self, module: str, as_package: bool = False) -> Set[str]:
-        # TODO optimise for as_package.
         if as_package:
             destination_modules = self._all_modules_in_package(module)
         else:
             destination_modules = {module}
+        return self._find_connected_modules(destination_modules, downstream=False)
 
-        upstream_modules = set()
-
-        for candidate in filter(lambda m: m not in destination_modules, self.modules):
-            for destination_module in destination_modules:
-                if networkx.algorithms.has_path(
-                    self._networkx_graph, destination_module, candidate
-                ):
-                    upstream_modules.add(candidate)
-                    break
-
-        return upstream_modules
-
+    @_query
     def find_shortest_chain(
         self, importer: str, imported: str
     ) -> Optional[Tuple[str, ...]]:
-        try:
-            return tuple(
-                networkx.algorithms.shortest_path(
-                    self._networkx_graph, source=importer, target=imported
-                )
-            )
-        except networkx.NetworkXNoPath:
-            return None
+        return self._find_shortest_chain_in(self._networkx_graph, importer, imported)
 
+    @_query
     def find_shortest_chains(
         self, importer: str, imported: str
     ) -> Set[Tuple[str, ...]]:
@@ -263,37 +472,42 @@ class ImportGraph(graph.AbstractImportGraph):
             # this context, so raise an exception.
             raise ValueError("Modules have shared descendants.")
 
+        # Rather than removing imports from the graph during the search, hide them using a view
+        # of the graph that leaves out any imports in this set. This means the graph itself is
+        # never altered, so other queries can safely use it at the same time.
+        hidden_imports: Set[Tuple[str, str]] = set()
+        graph_view = networkx.subgraph_view(
+            self._networkx_graph,
+            filter_edge=lambda importer, imported: (importer, imported) not in hidden_imports,
+        )
+
         imports_between_modules = self._find_all_imports_between_modules(
             upstream_modules
         ) | self._find_all_imports_between_modules(downstream_modules)
-        self._hide_any_existing_imports(imports_between_modules)
+        hidden_imports |= imports_between_modules
 
         map_of_imports = {}
         for module in upstream_modules | downstream_modules:
-            map_of_imports[module] = set(
-                (m, module) for m in self.find_modules_that_directly_import(module)
-            ) | set((module, m) for m in self.find_modules_directly_imported_by(module))
+            map_of_imports[module] = (
+                set((m, module) for m in self.find_modules_that_directly_import(module))
+                | set((module, m) for m in self.find_modules_directly_imported_by(module))
+            ) - imports_between_modules
         for imports in map_of_imports.values():
-            self._hide_any_existing_imports(imports)
+            hidden_imports |= imports
 
         for upstream in upstream_modules:
             imports_of_upstream_module = map_of_imports[upstream]
-            self._reveal_imports(imports_of_upstream_module)
+            hidden_imports -= imports_of_upstream_module
             for downstream in downstream_modules:
                 imports_by_downstream_module = map_of_imports[downstream]
-                self._reveal_imports(imports_by_downstream_module)
-                shortest_chain = self.find_shortest_chain(
-                    imported=upstream, importer=downstream
+                hidden_imports -= imports_by_downstream_module
+                shortest_chain = self._find_shortest_chain_in(
+                    graph_view, importer=downstream, imported=upstream
                 )
                 if shortest_chain:
                     shortest_chains.add(shortest_chain)
-                self._hide_any_existing_imports(imports_by_downstream_module)
-            self._hide_any_existing_imports(imports_of_upstream_module)
-
-        # Reveal all the hidden imports.
-        for imports in map_of_imports.values():
-            self._reveal_imports(imports)
-        self._reveal_imports(imports_between_modules)
+                hidden_imports |= imports_by_downstream_module
+            hidden_imports |= imports_of_upstream_module
 
         return shortest_chains
 
@@ -309,10 +523,20 @@ class ImportGraph(graph.AbstractImportGraph):
         # Cast the results to tuples.
         return wrap_generator(all_simple_paths, tuple)
 
+    @_query
     def chain_exists(self, importer: str, imported: str, as_packages=False) -> bool:
         if not as_packages:
-            return networkx.algorithms.has_path(
-                self._networkx_graph, source=importer, target=imported
+            if importer not in self.modules or imported not in self.modules:
+                # Let networkx raise its usual exception.
+                return networkx.algorithms.has_path(
+                    self._networkx_graph, source=importer, target=imported
+                )
+            condensation = self._get_reachability_index()
+            importer_component = condensation.graph["mapping"][importer]
+            imported_component = condensation.graph["mapping"][imported]
+            # Every module in a strongly connected component can reach every other one.
+            return importer_component == imported_component or (
+                imported_component in self._find_reachable_components(importer_component)
             )
         upstream_modules = self._all_modules_in_package(imported)
         downstream_modules = self._all_modules_in_package(importer)
@@ -331,8 +555,373 @@ class ImportGraph(graph.AbstractImportGraph):
 
         return False
 
+    # Runtime imports
+    # ---------------
+
+    def add_runtime_import(self, *, importer: str, imported: str) -> None:
+        self._runtime_imports.add((importer, imported))
+
+    def read_runtime_trace(self, filename: str) -> None:
+        with open(filename, encoding="utf-8") as trace_file:
+            for line in trace_file:
+                if not line.strip():
+                    continue
+                runtime_import = json.loads(line)
+                self.add_runtime_import(
+                    importer=runtime_import["importer"], imported=runtime_import["imported"]
+                )
+
+    def find_runtime_imports(self, exclude_static: bool = False) -> Set[Tuple[str, str]]:
+        if not exclude_static:
+            return set(self._runtime_imports)
+        return {
+            (importer, imported)
+            for importer, imported in self._runtime_imports
+            if not self._networkx_graph.has_edge(importer, imported)
+        }
+
+    def merge_runtime_imports(self) -> None:
+        for importer, imported in sorted(self._runtime_imports):
+            self.add_import(importer=importer, imported=imported)
+
+    # Exporting
+    # ---------
+
+    def to_gexf(self, filename: str) -> None:
+        networkx.write_gexf(self._build_annotated_networkx_graph(), filename)
+
+    def to_csv(self, filename: str) -> None:
+        with open(filename, "w", newline="", encoding="utf-8") as csv_file:
+            writer = csv.DictWriter(csv_file, fieldnames=CSV_FIELDNAMES)
+            writer.writeheader()
+            for importer, imported in sorted(self._networkx_graph.edges):
+                import_details = self.get_import_details(importer=importer, imported=imported)
+                if not import_details:
+                    writer.writerow({"importer": importer, "imported": imported})
+                for details in sorted(import_details, key=lambda d: d["line_number"]):
+                    writer.writerow({field: details[field] for field in CSV_FIELDNAMES})
+
+    @classmethod
+    def from_csv(cls, filename: str) -> "ImportGraph":
+        graph = cls()
+        with open(filename, newline="", encoding="utf-8") as csv_file:
+            for row in csv.DictReader(csv_file):
+                graph.add_import(
+                    importer=row["importer"],
+                    imported=row["imported"],
+                    line_number=int(row["line_number"]) if row["line_number"] else None,
+                    line_contents=row["line_contents"] or None,
+                )
+        return graph
+
+    def to_sqlite(self, filename: str) -> None:
+        connection = sqlite3.connect(filename)
+        try:
+            with connection:
+                connection.executescript(SQLITE_SCHEMA)
+                connection.executemany(
+                    "INSERT INTO modules VALUES (?, ?, ?, ?, ?, ?)",
+                    (
+                        (
+                            module,
+                            module in self._squashed_modules,
+                            self._external_module_kinds.get(module),
+                            self._module_provenances.get(module, {}).get("package_name"),
+                            self._module_provenances.get(module, {}).get("filename"),
+                            self._module_provenances.get(module, {}).get("mtime"),
+                        )
+                        for module in sorted(self.modules)
+                    ),
+                )
+                edges = sorted(self._networkx_graph.edges)
+                connection.executemany("INSERT INTO imports VALUES (?, ?)", edges)
+                connection.executemany(
+                    "INSERT INTO import_details VALUES ({})".format(
+                        ", ".join(f":{field}" for field in SQLITE_IMPORT_DETAILS_FIELDS)
+                    ),
+                    (
+                        dict(details, ignored_for=",".join(details["ignored_for"]))
+                        for importer, imported in edges
+                        for details in self.get_import_details(
+                            importer=importer, imported=imported
+                        )
+                    ),
+                )
+        finally:
+            connection.close()
+
+    def to_networkx_data(self) -> Dict[str, Any]:
+        return {
+            key: list(value) if isinstance(value, types.GeneratorType) else value
+            for key, value in self._get_networkx_data(canonical=False).items()
+        }
+
+    def to_json(self, canonical: bool = False) -> str:
+        json_file = io.StringIO()
+        self.write_json(json_file, canonical=canonical)
+        return json_file.getvalue()
+
+    def write_json(self, file: TextIO, canonical: bool = False) -> None:
+        if canonical:
+            dumps = functools.partial(json.dumps, sort_keys=True, separators=(",", ":"))
+            item_separator, key_separator = ",", ":"
+        else:
+            dumps = json.dumps
+            item_separator, key_separator = ", ", ": "
+        data = self._get_networkx_data(canonical=canonical)
+        file.write("{")
+        for index, key in enumerate(sorted(data) if canonical else data):
+            if index:
+                file.write(item_separator)
+            file.write(dumps(key) + key_separator)
+            value = data[key]
+            if not isinstance(value, types.GeneratorType):
+                file.write(dumps(value))
+                continue
+            # Write the nodes and links one at a time, rather than holding them all in memory.
+            file.write("[")
+            for item_index, item in enumerate(value):
+                if item_index:
+                    file.write(item_separator)
+                file.write(dumps(item))
+            file.write("]")
+        file.write("}")
+
+    @classmethod
+    def from_networkx_data(cls, data: Dict[str, Any]) -> "ImportGraph":
+        graph = cls()
+        for node in data["nodes"]:
+            graph.add_module(
+                node["id"],
+                is_squashed=node.get("is_squashed", False),
+                external_kind=node.get("external_kind"),
+            )
+            if "filename" in node:
+                graph.set_module_provenance(
+                    node["id"],
+                    package_name=node["package_name"],
+                    filename=node["filename"],
+                    mtime=node["mtime"],
+                )
+        # Later versions of networkx call the links 'edges'.
+        for link in data["links"] if "links" in data else data["edges"]:
+            import_details = link.get("import_details", [])
+            if not import_details:
+                graph.add_import(importer=link["source"], imported=link["target"])
+            for details in import_details:
+                graph.add_import(
+                    importer=link["source"],
+                    imported=link["target"],
+                    **dict(details, ignored_for=tuple(details.get("ignored_for", ()))),
+                )
+        if "build_info" in data.get("graph", {}):
+            graph.set_build_info(data["graph"]["build_info"])
+        for importer, imported in data.get("graph", {}).get("runtime_imports", []):
+            graph.add_runtime_import(importer=importer, imported=imported)
+        return graph
+
+    def to_html(self, filename: str, container: Optional[str] = None) -> None:
+        if container is not None and self.is_module_squashed(container):
+            raise ValueError("Cannot visualise the contents of a squashed module.")
+        package_level_imports: Dict[Tuple[str, str], int] = {}
+        for importer, imported in self._networkx_graph.edges:
+            importer_package = self._find_package_in_container(importer, container)
+            imported_package = self._find_package_in_container(imported, container)
+            if importer_package and imported_package and importer_package != imported_package:
+                key = (importer_package, imported_package)
+                package_level_imports[key] = package_level_imports.get(key, 0) + 1
+        packages = {self._find_package_in_container(module, container) for module in self.modules}
+        packages.discard(None)
+        nodes = []
+        for package in sorted(packages):
+            # The package itself may not be in the graph, if it's a namespace package.
+            attributes = (
+                self._get_module_attributes(package)
+                if package in self.modules
+                else {"is_squashed": False}
+            )
+            label = package if container is None else package.split(".")[-1]
+            nodes.append(dict(attributes, id=package, label=label))
+        data = {
+            "nodes": nodes,
+            "links": [
+                {"source": importer, "target": imported, "weight": weight}
+                for (importer, imported), weight in sorted(package_level_imports.items())
+            ],
+        }
+        with open(filename, "w", encoding="utf-8") as html_file:
+            html_file.write(
+                htmlviewer.HTML_TEMPLATE.substitute(
+                    title=html.escape(container or "Import graph"),
+                    # Escape '<', so that the data can't close the script element it's embedded in.
+                    data=json.dumps(data).replace("<", "\\u003c"),
+                )
+            )
+
+    def save(self, filename: str) -> None:
+        with open(filename, "w", encoding="utf-8") as graph_file:
+            self.write_json(graph_file)
+
+    @classmethod
+    def load(cls, filename: str) -> "ImportGraph":
+        try:
+            with open(filename, encoding="utf-8") as graph_file:
+                data = json.load(graph_file)
+        except (UnicodeDecodeError, json.JSONDecodeError):
+            data = None
+        is_graph_data = (
+            isinstance(data, dict) and "nodes" in data and ("links" in data or "edges" in data)
+        )
+        if not is_graph_data:
+            raise ValueError(f"{filename} does not contain a saved {cls.__name__}.")
+        return cls.from_networkx_data(data)
+
+    def export_neighbourhood(self, module: str, radius: int, format: str, filename: str) -> None:
+        if module not in self.modules:
+            raise ModuleNotPresent(f'"{module}" not present in the graph.')
+        if radius < 0:
+            raise ValueError("Radius must not be negative.")
+        if format not in ExportFormat.ALL:
+            raise ValueError(f"Unknown export format {format}.")
+
+        distances = networkx.single_source_shortest_path_length(
+            self._networkx_graph.to_undirected(as_view=True), module, cutoff=radius
+        )
+        neighbourhood = self._build_subgraph(set(distances))
+
+        if format == ExportFormat.JSON:
+            with open(filename, "w", encoding="utf-8") as json_file:
+                neighbourhood.write_json(json_file)
+        else:
+            getattr(neighbourhood, f"to_{format}")(filename)
+
+    # Importing from other tools
+    # --------------------------
+
+    @classmethod
+    def from_edge_list(cls, edges: Iterable[Tuple[str, str]]) -> "ImportGraph":
+        graph = cls()
+        for importer, imported in edges:
+            graph.add_import(importer=importer, imported=imported)
+        return graph
+
+    @classmethod
+    def from_pydeps(cls, filename: str) -> "ImportGraph":
+        with open(filename, encoding="utf-8") as pydeps_file:
+            pydeps_modules = json.load(pydeps_file)
+        graph = cls.from_edge_list(
+            (module, imported)
+            for module, pydeps_module in pydeps_modules.items()
+            for imported in pydeps_module.get("imports", [])
+        )
+        # Include any modules that neither import nor are imported.
+        for module in pydeps_modules:
+            graph.add_module(module)
+        return graph
+
+    @classmethod
+    def from_snakefood(cls, filename: str) -> "ImportGraph":
+        graph = cls()
+        with open(filename, encoding="utf-8") as snakefood_file:
+            for line in snakefood_file:
+                if not line.strip():
+                    continue
+                # Each line is the repr of a tuple, in the form
+                # ((root, relative_filename), (dependency_root, dependency_relative_filename)).
+                (_, relative_filename), (_, dependency_relative_filename) = ast.literal_eval(line)
+                module = cls._module_name_from_relative_filename(relative_filename)
+                graph.add_module(module)
+                # Files without any dependencies are listed with a dependency of (None, None).
+                if dependency_relative_filename is not None:
+                    graph.add_import(
+                        importer=module,
+                        imported=cls._module_name_from_relative_filename(
+                            dependency_relative_filename
+                        ),
+                    )
+        return graph
+
     # Private methods
 
+    @staticmethod
+    def _find_shortest_chain_in(
+        networkx_graph: networkx.DiGraph, importer: str, imported: str
+    ) -> Optional[Tuple[str, ...]]:
+        """
+        Return the shortest chain from the importer to the imported module in the supplied
+        networkx graph (or view of a graph), or None if there isn't one.
+        """
+        try:
+            return tuple(
+                networkx.algorithms.shortest_path(networkx_graph, source=importer, target=imported)
+            )
+        except networkx.NetworkXNoPath:
+            return None
+
+    def _get_reachability_index(self) -> networkx.DiGraph:
+        """
+        Return the condensation of the graph: a directed acyclic graph with a node for each
+        strongly connected component (i.e. each set of modules that can all reach each other).
+
+        The condensation is built the first time it's needed after the graph changes, so that
+        repeated calls to chain_exists, find_downstream_modules and find_upstream_modules don't
+        each have to search the whole graph.
+        """
+        if self._condensation is None:
+            self._condensation = networkx.condensation(self._networkx_graph)
+            self._reachable_components = {}
+            self._reaching_components = {}
+        return self._condensation
+
+    def _find_reachable_components(self, component: int) -> Set[int]:
+        """
+        Return the components in the reachability index that the supplied component can reach.
+        """
+        if component not in self._reachable_components:
+            self._reachable_components[component] = networkx.descendants(
+                self._get_reachability_index(), component
+            )
+        return self._reachable_components[component]
+
+    def _find_reaching_components(self, component: int) -> Set[int]:
+        """
+        Return the components in the reachability index that can reach the supplied component.
+        """
+        if component not in self._reaching_components:
+            self._reaching_components[component] = networkx.ancestors(
+                self._get_reachability_index(), component
+            )
+        return self._reaching_components[component]
+
+    def _find_connected_modules(self, modules: Set[str], downstream: bool) -> Set[str]:
+        """
+        Return the modules, other than those supplied, that import any of the supplied modules
+        (if downstream), or that are imported by them (otherwise), directly or indirectly.
+        """
+        for module in modules:
+            if module not in self.modules:
+                raise ModuleNotPresent(f'"{module}" not present in the graph.')
+        condensation = self._get_reachability_index()
+        components = set()
+        for module in modules:
+            component = condensation.graph["mapping"][module]
+            components.add(component)
+            if downstream:
+                components |= self._find_reaching_components(component)
+            else:
+                components |= self._find_reachable_components(component)
+        connected_modules: Set[str] = set()
+        for component in components:
+            connected_modules |= condensation.nodes[component]["members"]
+        return connected_modules - modules
+
+    def _invalidate_indexes(self) -> None:
+        self._condensation = None
+        self._reachable_components = {}
+        self._reaching_components = {}
+        if self._query_cache is not None:
+            self._query_cache.clear()
+
     def _find_ancestor_squashed_module(self, module: str) -> Optional[str]:
         """
         Return the name of a squashed module that is an ancestor of the supplied module, or None
@@ -382,25 +971,123 @@ class ImportGraph(graph.AbstractImportGraph):
                     imports.add((importer, imported))
         return imports
 
-    def _hide_any_existing_imports(self, imports: Set[Tuple[str, str]]) -> None:
+    def _build_annotated_networkx_graph(self) -> networkx.DiGraph:
         """
-        Temporarily remove the supplied direct imports from the graph.
-
-        If an import is not in the graph, or already hidden, this will have no effect.
+        Return a copy of the underlying networkx graph, with the metadata about each module and
+        import added as node and edge attributes.
+        """
+        annotated_graph = networkx.DiGraph()
+        for module in sorted(self.modules):
+            annotated_graph.add_node(module, **self._get_module_attributes(module))
+        for importer, imported in sorted(self._networkx_graph.edges):
+            number_of_statements = len(
+                self.get_import_details(importer=importer, imported=imported)
+            )
+            annotated_graph.add_edge(importer, imported, weight=number_of_statements or 1)
+        return annotated_graph
 
-        Args:
-            imports: Set of direct imports, in the form (importer, imported).
+    def _build_subgraph(self, modules: Set[str]) -> "ImportGraph":
+        """
+        Return a new graph containing the supplied modules and the imports between them, along
+        with their metadata.
+        """
+        subgraph = self.__class__()
+        for module in sorted(modules):
+            subgraph.add_module(
+                module,
+                is_squashed=module in self._squashed_modules,
+                external_kind=self._external_module_kinds.get(module),
+            )
+            if module in self._module_provenances:
+                subgraph.set_module_provenance(module, **self._module_provenances[module])
+        for importer, imported in self._networkx_graph.subgraph(modules).edges:
+            import_details = self.get_import_details(importer=importer, imported=imported)
+            if not import_details:
+                subgraph.add_import(importer=importer, imported=imported)
+            for details in import_details:
+                subgraph.add_import(**details)
+        return subgraph
+
+    def _get_networkx_data(self, canonical: bool) -> Dict[str, Any]:
+        """
+        Return the node-link data for the graph (see to_networkx_data), with the nodes and links
+        as generators. If canonical, leave out the data that varies between builds of the same
+        code, and sort the import details.
+        """
+        graph_attributes: Dict[str, Any] = {}
+        if self._build_info is not None and not canonical:
+            graph_attributes["build_info"] = self.build_info()
+        if self._runtime_imports and not canonical:
+            graph_attributes["runtime_imports"] = [
+                [importer, imported] for importer, imported in sorted(self._runtime_imports)
+            ]
+        return {
+            "directed": True,
+            "multigraph": False,
+            "graph": graph_attributes,
+            "nodes": self._generate_networkx_nodes(canonical),
+            "links": self._generate_networkx_links(canonical),
+        }
+
+    def _generate_networkx_nodes(self, canonical: bool) -> Iterator[Dict[str, Any]]:
+        for module in sorted(self.modules):
+            node = dict(self._get_module_attributes(module), id=module)
+            if canonical:
+                for key in ("package_name", "filename", "mtime"):
+                    node.pop(key, None)
+            yield node
+
+    def _generate_networkx_links(self, canonical: bool) -> Iterator[Dict[str, Any]]:
+        for importer, imported in sorted(self._networkx_graph.edges):
+            import_details = [
+                {
+                    field: list(value) if field == "ignored_for" else value
+                    for field, value in details.items()
+                    if field not in ("importer", "imported")
+                }
+                for details in self.get_import_details(importer=importer, imported=imported)
+            ]
+            if canonical:
+                import_details.sort(
+                    key=lambda d: (d["line_number"], json.dumps(d, sort_keys=True))
+                )
+            yield {
+                "source": importer,
+                "target": imported,
+                "weight": len(import_details) or 1,
+                "import_details": import_details,
+            }
+
+    @staticmethod
+    def _module_name_from_relative_filename(relative_filename: str) -> str:
+        """
+        Return the name of the module in a file, given its path relative to the directory that
+        contains its root package, e.g. 'mypackage/foo/__init__.py' -> 'mypackage.foo'.
         """
-        for importer, imported in tuple(imports):
-            if self._networkx_graph.has_edge(importer, imported):
-                self._networkx_graph.remove_edge(importer, imported)
+        components = os.path.splitext(os.path.normpath(relative_filename))[0].split(os.sep)
+        if len(components) > 1 and components[-1] == "__init__":
+            components = components[:-1]
+        return ".".join(components)
 
-    def _reveal_imports(self, imports: Set[Tuple[str, str]]) -> None:
+    @staticmethod
+    def _find_package_in_container(module: str, container: Optional[str]) -> Optional[str]:
         """
-        Given a set of direct imports that were hidden by _hide_any_existing_imports, add them back.
+        Return the child of the container that the module is in (or is), or, if the container is
+        None, the module's root package. Returns None if the module isn't in the container.
+        """
+        if container is None:
+            return Module(module).package_name
+        if not Module(module).is_descendant_of(Module(container)):
+            return None
+        child_name = module[len(container) + 1 :].split(".")[0]
+        return f"{container}.{child_name}"
 
-        Args:
-            imports: Set of direct imports, in the form (importer, imported).
+    def _get_module_attributes(self, module: str) -> Dict[str, Any]:
+        """
+        Return the metadata about a module, as exported by to_gexf and to_networkx_data.
         """
-        for importer, imported in tuple(imports):
-            self._networkx_graph.add_edge(importer, imported)
+        attributes: Dict[str, Any] = {"is_squashed": module in self._squashed_modules}
+        if module in self._external_module_kinds:
+            attributes["external_kind"] = self._external_module_kinds[module]
+        attributes.update(self._module_provenances.get(module, {}))
+        return attributes
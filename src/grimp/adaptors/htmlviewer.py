@@ -0,0 +1,175 @@
+"""
+The standalone HTML page written by ImportGraph.to_html.
+
+The page has no external dependencies: the graph data is embedded in it as JSON, and it is laid out
+by a small force-directed layout written in plain JavaScript.
+"""
+import string
+
+HTML_TEMPLATE = string.Template(
+    """<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<title>${title}</title>
+<style>
+  html, body { margin: 0; height: 100%; font-family: sans-serif; background: #fafafa; }
+  header { position: absolute; top: 0; left: 0; padding: 8px 12px; pointer-events: none; }
+  header h1 { margin: 0; font-size: 16px; }
+  header p { margin: 4px 0 0; font-size: 12px; color: #555; }
+  svg { display: block; width: 100%; height: 100%; cursor: grab; }
+  .link { stroke: #999; stroke-opacity: 0.6; }
+  .link.highlighted { stroke: #d62728; stroke-opacity: 1; }
+  .node circle { fill: #1f77b4; stroke: #fff; stroke-width: 1.5; cursor: pointer; }
+  .node.external circle { fill: #aaa; }
+  .node.squashed circle { stroke: #333; stroke-dasharray: 2 2; }
+  .node.highlighted circle { fill: #d62728; }
+  .node.faded { opacity: 0.2; }
+  .node text { font-size: 11px; pointer-events: none; }
+</style>
+</head>
+<body>
+<header>
+  <h1>${title}</h1>
+  <p>Drag modules to move them. Click a module to highlight its imports.</p>
+</header>
+<svg id="graph">
+  <defs>
+    <marker id="arrow" viewBox="0 -5 10 10" refX="18" markerWidth="6" markerHeight="6"
+            orient="auto">
+      <path d="M0,-5L10,0L0,5" fill="#999"></path>
+    </marker>
+  </defs>
+  <g id="viewport"></g>
+</svg>
+<script type="application/json" id="graph-data">${data}</script>
+<script>
+(function () {
+  var data = JSON.parse(document.getElementById("graph-data").textContent);
+  var svg = document.getElementById("graph");
+  var viewport = document.getElementById("viewport");
+  var namespace = "http://www.w3.org/2000/svg";
+  var width = svg.clientWidth, height = svg.clientHeight;
+  var nodesById = {};
+
+  function create(name, attributes, parent) {
+    var element = document.createElementNS(namespace, name);
+    Object.keys(attributes).forEach(function (key) {
+      element.setAttribute(key, attributes[key]);
+    });
+    parent.appendChild(element);
+    return element;
+  }
+
+  data.nodes.forEach(function (node, index) {
+    var angle = 2 * Math.PI * index / data.nodes.length;
+    node.x = width / 2 + Math.cos(angle) * width / 4;
+    node.y = height / 2 + Math.sin(angle) * height / 4;
+    node.vx = 0;
+    node.vy = 0;
+    nodesById[node.id] = node;
+  });
+  data.links.forEach(function (link) {
+    link.source = nodesById[link.source];
+    link.target = nodesById[link.target];
+    link.element = create("line", {
+      "class": "link",
+      "marker-end": "url(#arrow)",
+      "stroke-width": 1 + Math.log(link.weight)
+    }, viewport);
+    create("title", {}, link.element).textContent = link.source.id + " -> " + link.target.id +
+      " (" + link.weight + (link.weight === 1 ? " import)" : " imports)");
+  });
+  data.nodes.forEach(function (node) {
+    var classes = ["node"];
+    if (node.external_kind) { classes.push("external"); }
+    if (node.is_squashed) { classes.push("squashed"); }
+    node.element = create("g", {"class": classes.join(" ")}, viewport);
+    create("circle", {r: 7}, node.element);
+    create("title", {}, node.element).textContent = node.id;
+    create("text", {x: 10, y: 4}, node.element).textContent = node.label;
+  });
+
+  function tick() {
+    var nodes = data.nodes;
+    for (var i = 0; i < nodes.length; i++) {
+      for (var j = i + 1; j < nodes.length; j++) {
+        var dx = nodes[j].x - nodes[i].x || 0.01, dy = nodes[j].y - nodes[i].y || 0.01;
+        var distanceSquared = Math.max(dx * dx + dy * dy, 100);
+        var repulsion = 2000 / distanceSquared;
+        nodes[i].vx -= dx * repulsion / 10; nodes[i].vy -= dy * repulsion / 10;
+        nodes[j].vx += dx * repulsion / 10; nodes[j].vy += dy * repulsion / 10;
+      }
+    }
+    data.links.forEach(function (link) {
+      var dx = link.target.x - link.source.x, dy = link.target.y - link.source.y;
+      var distance = Math.sqrt(dx * dx + dy * dy) || 1;
+      var attraction = (distance - 120) / distance * 0.02;
+      link.source.vx += dx * attraction; link.source.vy += dy * attraction;
+      link.target.vx -= dx * attraction; link.target.vy -= dy * attraction;
+    });
+    nodes.forEach(function (node) {
+      node.vx += (width / 2 - node.x) * 0.002;
+      node.vy += (height / 2 - node.y) * 0.002;
+      if (node !== dragged) {
+        node.x += node.vx; node.y += node.vy;
+      }
+      node.vx *= 0.6; node.vy *= 0.6;
+      node.element.setAttribute("transform", "translate(" + node.x + "," + node.y + ")");
+    });
+    data.links.forEach(function (link) {
+      link.element.setAttribute("x1", link.source.x);
+      link.element.setAttribute("y1", link.source.y);
+      link.element.setAttribute("x2", link.target.x);
+      link.element.setAttribute("y2", link.target.y);
+    });
+    window.requestAnimationFrame(tick);
+  }
+
+  var dragged = null, selected = null;
+  function position(event) {
+    var point = svg.createSVGPoint();
+    point.x = event.clientX;
+    point.y = event.clientY;
+    return point.matrixTransform(viewport.getScreenCTM().inverse());
+  }
+  function select(node) {
+    selected = selected === node ? null : node;
+    var neighbours = {};
+    data.links.forEach(function (link) {
+      var isSelected = selected && (link.source === selected || link.target === selected);
+      link.element.classList.toggle("highlighted", Boolean(isSelected));
+      if (isSelected) { neighbours[link.source.id] = neighbours[link.target.id] = true; }
+    });
+    data.nodes.forEach(function (other) {
+      other.element.classList.toggle("highlighted", other === selected);
+      other.element.classList.toggle("faded", Boolean(selected && !neighbours[other.id]) &&
+        other !== selected);
+    });
+  }
+  data.nodes.forEach(function (node) {
+    node.element.addEventListener("mousedown", function (event) {
+      dragged = node;
+      event.stopPropagation();
+    });
+    node.element.addEventListener("click", function () { select(node); });
+  });
+  svg.addEventListener("mousemove", function (event) {
+    if (dragged) {
+      var point = position(event);
+      dragged.x = point.x;
+      dragged.y = point.y;
+    }
+  });
+  window.addEventListener("mouseup", function () { dragged = null; });
+  window.addEventListener("resize", function () {
+    width = svg.clientWidth;
+    height = svg.clientHeight;
+  });
+  tick();
+})();
+</script>
+</body>
+</html>
+"""
+)
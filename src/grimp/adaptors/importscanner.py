@@ -1,13 +1,63 @@
 import ast
 import logging
-from typing import List, Set
+import re
+import sys
+from typing import Dict, FrozenSet, Iterator, List, Optional, Set, Tuple
 
 from grimp.application.ports.importscanner import AbstractImportScanner
-from grimp.domain.valueobjects import DirectImport, Module
+from grimp.domain.valueobjects import (
+    DirectImport,
+    ExternalImportGranularity,
+    ImportScope,
+    Module,
+)
 from grimp import exceptions
 
 logger = logging.getLogger(__name__)
 
+# The names of the constants that guard imports only needed for type checking, unless others
+# are supplied.
+DEFAULT_TYPE_CHECKING_GUARDS = frozenset({"TYPE_CHECKING", "typing.TYPE_CHECKING"})
+
+# Matches a '# grimp: ignore' comment, optionally followed by a list of names in brackets.
+_IGNORE_DIRECTIVE_REGEX = re.compile(r"#\s*grimp:\s*ignore(?:\[(?P<names>[^\]]*)\])?")
+
+# The names that, when used in the condition of an 'if' statement, make the imports within it
+# platform-specific.
+PLATFORM_NAMES = frozenset(
+    {
+        "sys.platform",
+        "os.name",
+        "platform.system",
+        "platform.machine",
+        "platform.python_implementation",
+        "sys.implementation.name",
+    }
+)
+
+# The names that, when used in the condition of an 'if' statement, make the imports within it
+# depend on the Python version.
+VERSION_NAMES = frozenset({"sys.version_info", "sys.version", "sys.hexversion"})
+
+# The exceptions that, if caught, make the imports whose failure they would catch optional.
+IMPORT_ERROR_NAMES = frozenset({"ImportError", "ModuleNotFoundError"})
+
+# The extensions of the files that may contain a module's source code, when including Cython.
+SOURCE_FILE_EXTENSIONS = (".py", ".pyx", ".pxd")
+CYTHON_FILE_EXTENSIONS = (".pyx", ".pxd")
+
+# Patterns for the Cython statements that are relevant to finding imports.
+_CYTHON_IMPORT_REGEX = re.compile(r"(?:from\s+\S+\s+c?import|c?import)\s")
+_CYTHON_CIMPORT_REGEX = re.compile(r"\bcimport\b")
+_CYTHON_CLASS_REGEX = re.compile(r"(?:cdef\s+)?class\s.*:$")
+_CYTHON_FUNCTION_REGEX = re.compile(
+    r"(?:async\s+def|def|cpdef|cdef(?!\s+(?:class|extern)\b))\s.*:$"
+)
+
+# A module imported by a statement, along with the full name of the object imported from it
+# (or None, if the module itself is imported).
+_ImportedModule = Tuple[Module, Optional[str]]
+
 
 class NotAnImport(Exception):
     pass
@@ -19,6 +69,12 @@ class ImportScanner(AbstractImportScanner):
         # We gain a big performance increase by building the set of root modules once,
         # instead of letting each node parser figure them out from the internal modules.
         self._root_modules = {module.root for module in self.modules}
+        self._type_checking_guards = DEFAULT_TYPE_CHECKING_GUARDS | self.type_checking_guards
+        # Cache of the names in each module's __all__, used to resolve wildcard imports.
+        self._all_names_by_module: Dict[Module, Optional[List[str]]] = {}
+        # Cache of the names bound by import statements in each module, used to resolve
+        # re-exports.
+        self._imports_by_bound_name_by_module: Dict[Module, Dict[str, ast.ImportFrom]] = {}
 
     def scan_for_imports(self, module: Module) -> Set[DirectImport]:
         """
@@ -27,9 +83,21 @@ class ImportScanner(AbstractImportScanner):
         because you can't know whether "from foo.bar import baz" is importing a module
         called  `baz`, or a function `baz` from the module `bar`.)
         """
+        if not self.include_cython:
+            return self._scan_python_file(module, self._determine_module_filename(module))
+
+        direct_imports: Set[DirectImport] = set()
+        for module_filename in self._determine_module_filenames(module):
+            if module_filename.endswith(CYTHON_FILE_EXTENSIONS):
+                direct_imports |= self._scan_cython_file(module, module_filename)
+            else:
+                direct_imports |= self._scan_python_file(module, module_filename)
+        return direct_imports
+
+    def _scan_python_file(self, module: Module, module_filename: str) -> Set[DirectImport]:
+        self._check_file_size(module_filename)
         direct_imports: Set[DirectImport] = set()
 
-        module_filename = self._determine_module_filename(module)
         is_package = self._module_is_package(module_filename)
         module_contents = self._read_module_contents(module_filename)
         module_lines = module_contents.splitlines()
@@ -41,15 +109,170 @@ class ImportScanner(AbstractImportScanner):
                 lineno=e.lineno,
                 text=e.text,
             )
-        for node in ast.walk(ast_tree):
+        if self.include_dynamic_imports:
+            import_module_names = _find_import_module_names(ast_tree)
+        else:
+            import_module_names = set()
+        type_checking_nodes = _find_type_checking_nodes(ast_tree, self._type_checking_guards)
+        platform_conditions = _find_conditions(ast_tree, module_lines, PLATFORM_NAMES)
+        version_conditions = _find_conditions(ast_tree, module_lines, VERSION_NAMES)
+        optional_nodes = _find_optional_nodes(ast_tree)
+        for node, scope in _walk_with_scope(ast_tree):
             direct_imports |= self._parse_direct_imports_from_node(
-                node, module, module_lines, is_package
+                node,
+                scope,
+                module,
+                module_lines,
+                is_package,
+                import_module_names,
+                is_type_checking=node in type_checking_nodes,
+                platform_condition=platform_conditions.get(node),
+                is_optional=node in optional_nodes,
+                version_condition=version_conditions.get(node),
+            )
+        if import_module_names:
+            direct_imports |= self._parse_lazy_imports(
+                ast_tree, module, module_lines, is_package, import_module_names
             )
 
         return direct_imports
 
+    def _parse_lazy_imports(
+        self,
+        ast_tree: ast.Module,
+        module: Module,
+        module_lines: List[str],
+        is_package: bool,
+        import_module_names: Set[str],
+    ) -> Set[DirectImport]:
+        """
+        Find the imports made lazily by a module-level __getattr__ function (see PEP 562) that
+        calls import_module with names taken from literal collections in the module, e.g.:
+
+            _submodules = ["foo", "bar"]
+
+            def __getattr__(name):
+                if name in _submodules:
+                    return importlib.import_module(f"{__name__}.{name}")
+
+        Each name in the collections that the function refers to is tried in turn, and an
+        import is recorded for each resulting internal module.
+        """
+        literal_collections = _find_literal_collections(ast_tree)
+        direct_imports: Set[DirectImport] = set()
+
+        for function in ast_tree.body:
+            if not (isinstance(function, ast.FunctionDef) and function.name == "__getattr__"):
+                continue
+            candidate_names = {
+                name
+                for node in ast.walk(function)
+                if isinstance(node, ast.Name) and node.id in literal_collections
+                for name in literal_collections[node.id]
+            }
+            for node in ast.walk(function):
+                if not (
+                    isinstance(node, ast.Call)
+                    and node.args
+                    and _get_dotted_name(node.func) in import_module_names
+                ):
+                    continue
+                line_contents = module_lines[node.lineno - 1].strip()
+                ignored_for = _parse_node_ignore_directive(node, module_lines)
+                if ignored_for == ():
+                    continue
+                for candidate_name in candidate_names:
+                    imported_name = _render_string_template(
+                        node.args[0], {"__name__": module.name}, candidate_name
+                    )
+                    if imported_name and imported_name.startswith("."):
+                        package = module if is_package else module.parent
+                        imported_name = f"{package.name}{imported_name}"
+                    if not imported_name or Module(imported_name) not in self.modules:
+                        continue
+                    direct_imports.add(
+                        DirectImport(
+                            importer=module,
+                            imported=Module(imported_name),
+                            line_number=node.lineno,
+                            line_contents=line_contents,
+                            is_dynamic=True,
+                            scope=ImportScope.FUNCTION,
+                            ignored_for=ignored_for or (),
+                            column=node.col_offset,
+                            end_line_number=_get_end_line_number(node, module_lines),
+                            is_lazy=True,
+                        )
+                    )
+        return direct_imports
+
+    def _scan_cython_file(self, module: Module, module_filename: str) -> Set[DirectImport]:
+        """
+        Scan a Cython (.pyx or .pxd) file for imports, including cimports.
+
+        Cython can't be parsed with the ast module, so import statements are found line by line,
+        then parsed as their Python equivalents.
+        """
+        self._check_file_size(module_filename)
+        direct_imports: Set[DirectImport] = set()
+
+        is_package = self._module_is_package(module_filename)
+        module_lines = self._read_module_contents(module_filename).splitlines()
+        for (
+            line_number,
+            end_line_number,
+            column,
+            source,
+            scope,
+            is_cimport,
+        ) in _find_cython_imports(module_lines):
+            try:
+                nodes = ast.parse(source).body
+            except SyntaxError:
+                logger.warning(
+                    f"Could not parse import in {module_filename}, line {line_number}: {source}"
+                )
+                continue
+            for node in nodes:
+                # Give the node the position of the original statement.
+                node.lineno = line_number
+                node.end_lineno = end_line_number
+                node.col_offset = column
+                direct_imports |= self._parse_direct_imports_from_node(
+                    node,
+                    scope,
+                    module,
+                    module_lines,
+                    is_package,
+                    import_module_names=set(),
+                    is_type_checking=False,
+                    is_cimport=is_cimport,
+                )
+
+        return direct_imports
+
+    def _check_file_size(self, module_filename: str) -> None:
+        if self.max_file_size is None:
+            return
+        size = self.file_system.getsize(module_filename)
+        if size > self.max_file_size:
+            raise exceptions.SourceFileTooLarge(
+                filename=module_filename, size=size, max_file_size=self.max_file_size
+            )
+
     def _parse_direct_imports_from_node(
-        self, node: ast.AST, module: Module, module_lines: List[str], is_package: bool
+        self,
+        node: ast.AST,
+        scope: str,
+        module: Module,
+        module_lines: List[str],
+        is_package: bool,
+        import_module_names: Set[str],
+        is_type_checking: bool,
+        is_cimport: bool = False,
+        platform_condition: Optional[str] = None,
+        is_optional: bool = False,
+        version_condition: Optional[str] = None,
     ) -> Set[DirectImport]:
         """
         Parse an ast node into a set of DirectImports.
@@ -61,50 +284,224 @@ class ImportScanner(AbstractImportScanner):
                 internal_modules=self.modules,
                 root_modules=self._root_modules,
                 is_package=is_package,
+                import_module_names=import_module_names,
+                external_import_granularity=self.external_import_granularity,
             )
         except NotAnImport:
             return set()
 
         direct_imports: Set[DirectImport] = set()
 
-        for imported in parser.determine_imported_modules(
+        line_contents = module_lines[node.lineno - 1].strip()
+        ignored_for = _parse_node_ignore_directive(node, module_lines)
+        if ignored_for == ():
+            # A bare '# grimp: ignore' excludes the import altogether.
+            return set()
+
+        imported_modules = parser.determine_imported_modules(
             include_external_packages=self.include_external_packages
-        ):
+        )
+        for unresolved_module_name in parser.unresolved_module_names:
+            self.unresolved_imports.setdefault(module, []).append(
+                {"name": unresolved_module_name, "line_number": node.lineno}
+            )
+            self.warnings.append(
+                exceptions.UnresolvedImportWarning(
+                    f"Could not resolve import of {unresolved_module_name} in {module}, "
+                    f"line {node.lineno}."
+                )
+            )
+        if self.resolve_wildcard_imports:
+            imported_modules = self._resolve_wildcard_imports(imported_modules)
+        if self.resolve_reexports:
+            imported_modules = {
+                self._resolve_reexport(imported, imported_object)
+                for imported, imported_object in imported_modules
+            }
+        if not self.include_imported_objects:
+            # Only record each module imported by the statement once.
+            imported_modules = {(imported, None) for imported, _ in imported_modules}
+
+        for imported, imported_object in imported_modules:
             direct_imports.add(
                 DirectImport(
                     importer=module,
                     imported=imported,
                     line_number=node.lineno,
-                    line_contents=module_lines[node.lineno - 1].strip(),
+                    line_contents=line_contents,
+                    is_dynamic=parser.is_dynamic,
+                    scope=scope,
+                    imported_object=imported_object,
+                    is_type_checking=is_type_checking,
+                    ignored_for=ignored_for or (),
+                    is_cimport=is_cimport,
+                    column=node.col_offset,
+                    end_line_number=_get_end_line_number(node, module_lines),
+                    platform_condition=platform_condition,
+                    is_optional=is_optional,
+                    version_condition=version_condition,
                 )
             )
 
         return direct_imports
 
+    def _resolve_wildcard_imports(
+        self, imported_modules: Set[_ImportedModule]
+    ) -> Set[_ImportedModule]:
+        """
+        Replace any wildcard imports (e.g. 'from foo import *') with imports of the names in the
+        imported module's __all__.
+
+        Wildcard imports are left as they are if the __all__ can't be determined statically.
+        """
+        resolved_imported_modules: Set[_ImportedModule] = set()
+        for imported, imported_object in imported_modules:
+            all_names = None
+            if imported_object == f"{imported.name}.*":
+                all_names = self._get_all_names(imported)
+            if not all_names:
+                resolved_imported_modules.add((imported, imported_object))
+                continue
+
+            for name in all_names:
+                candidate_module = Module(f"{imported.name}.{name}")
+                if candidate_module in self.modules:
+                    resolved_imported_modules.add((candidate_module, None))
+                else:
+                    resolved_imported_modules.add((imported, candidate_module.name))
+        return resolved_imported_modules
+
+    def _get_all_names(self, module: Module) -> Optional[List[str]]:
+        """
+        Return the names in the module's __all__, or None if they can't be determined.
+        """
+        if module not in self._all_names_by_module:
+            try:
+                module_contents = self._read_module_contents(
+                    self._determine_module_filename(module)
+                )
+                ast_tree = ast.parse(module_contents)
+            except (FileNotFoundError, SyntaxError, exceptions.SourceFileUndecodable):
+                # Any error will be reported when the module itself is scanned.
+                all_names = None
+            else:
+                all_names = _find_all_names(ast_tree)
+            self._all_names_by_module[module] = all_names
+        return self._all_names_by_module[module]
+
+    def _resolve_reexport(
+        self, imported: Module, imported_object: Optional[str]
+    ) -> _ImportedModule:
+        """
+        If the imported object is re-exported by the imported module (for example, by an
+        __init__.py containing 'from .foo import Thing'), return the module it is imported
+        from instead, following any further re-exports.
+        """
+        visited: Set[Tuple[Module, str]] = set()
+        while imported_object:
+            name = imported_object[len(imported.name) + 1 :]
+            if (imported, name) in visited:
+                # The re-exports are circular.
+                break
+            visited.add((imported, name))
+
+            imports_by_bound_name = self._get_imports_by_bound_name(imported)
+            if name not in imports_by_bound_name:
+                break
+            parser = _ImportFromNodeParser(
+                node=imports_by_bound_name[name],
+                module=imported,
+                internal_modules=self.modules,
+                root_modules=self._root_modules,
+                is_package=self._module_is_package(self._determine_module_filename(imported)),
+            )
+            # Only resolve re-exports to internal modules.
+            reexported_modules = parser.determine_imported_modules(
+                include_external_packages=False
+            )
+            if not reexported_modules:
+                break
+            [(imported, imported_object)] = reexported_modules
+        return imported, imported_object
+
+    def _get_imports_by_bound_name(self, module: Module) -> Dict[str, ast.ImportFrom]:
+        """
+        Return the module-scope 'from' imports in the module, keyed by the names they bind.
+
+        Each import statement only includes the name in question; for example, 'from .foo
+        import a, b as c' would be returned as {'a': 'from .foo import a',
+        'c': 'from .foo import b as c'}.
+        """
+        if module not in self._imports_by_bound_name_by_module:
+            imports_by_bound_name: Dict[str, ast.ImportFrom] = {}
+            try:
+                module_contents = self._read_module_contents(
+                    self._determine_module_filename(module)
+                )
+                ast_tree = ast.parse(module_contents)
+            except (FileNotFoundError, SyntaxError, exceptions.SourceFileUndecodable):
+                # Any error will be reported when the module itself is scanned.
+                pass
+            else:
+                for node, scope in _walk_with_scope(ast_tree):
+                    if scope != ImportScope.MODULE or not isinstance(node, ast.ImportFrom):
+                        continue
+                    for alias in node.names:
+                        if alias.name == "*":
+                            continue
+                        imports_by_bound_name[alias.asname or alias.name] = ast.ImportFrom(
+                            module=node.module, names=[alias], level=node.level
+                        )
+            self._imports_by_bound_name_by_module[module] = imports_by_bound_name
+        return self._imports_by_bound_name_by_module[module]
+
     def _determine_module_filename(self, module: Module) -> str:
         """
         Work out the full filename of the given module.
 
         Any given module can either be a straight Python file (foo.py) or else a package
-        (in which case the file is an __init__.py within a directory).
+        (in which case the file is an __init__.py within a directory). The package directory may
+        be named differently from the package itself.
         """
         module_components = module.name.split(".")
         package_directory = self._lookup_module_package_directory(module)
-        package_directory_parts = self.file_system.split(package_directory)
-        assert (
-            module_components[0] == package_directory_parts[-1]
-        ), "The package directory should be the same as the first part of the module name."
 
         filename_root = self.file_system.join(package_directory, *module_components[1:])
         candidate_filenames = (
             f"{filename_root}.py",
             self.file_system.join(filename_root, "__init__.py"),
+        ) + tuple(
+            f"{filename_root}{extension}" for extension in self.additional_module_extensions
         )
         for candidate_filename in candidate_filenames:
             if self.file_system.exists(candidate_filename):
                 return candidate_filename
         raise FileNotFoundError(f"Could not find module {module}.")
 
+    def _determine_module_filenames(self, module: Module) -> List[str]:
+        """
+        Work out the full filenames of all the source files for the given module, including any
+        Cython implementation (.pyx) and declaration (.pxd) files.
+        """
+        module_components = module.name.split(".")
+        package_directory = self._lookup_module_package_directory(module)
+        filename_root = self.file_system.join(package_directory, *module_components[1:])
+        candidate_filenames = [
+            f"{filename_root}{extension}"
+            for extension in SOURCE_FILE_EXTENSIONS + self.additional_module_extensions
+        ] + [
+            self.file_system.join(filename_root, f"__init__{extension}")
+            for extension in SOURCE_FILE_EXTENSIONS
+        ]
+        module_filenames = [
+            candidate_filename
+            for candidate_filename in candidate_filenames
+            if self.file_system.exists(candidate_filename)
+        ]
+        if not module_filenames:
+            raise FileNotFoundError(f"Could not find module {module}.")
+        return module_filenames
+
     def _lookup_module_package_directory(self, module: Module) -> str:
         for package_directory, modules in self.modules_by_package_directory.items():
             if module in modules:
@@ -115,13 +512,20 @@ class ImportScanner(AbstractImportScanner):
         """
         Read the file contents of the module.
         """
-        return self.file_system.read(module_filename)
+        try:
+            return self.file_system.read(module_filename)
+        except (SyntaxError, UnicodeDecodeError) as e:
+            # A SyntaxError here indicates a problem with the encoding declaration, rather than
+            # the code itself.
+            raise exceptions.SourceFileUndecodable(filename=module_filename, reason=str(e))
 
     def _module_is_package(self, module_filename: str) -> bool:
         """
         Whether or not the supplied module filename is a package.
         """
-        return self.file_system.split(module_filename)[-1] == "__init__.py"
+        return self.file_system.split(module_filename)[-1] in {
+            f"__init__{extension}" for extension in SOURCE_FILE_EXTENSIONS
+        }
 
 
 class _BaseNodeParser:
@@ -129,6 +533,9 @@ class _BaseNodeParser:
     Works out from an AST node what the imported modules are.
     """
 
+    # Whether the node imports modules dynamically, rather than via an import statement.
+    is_dynamic = False
+
     def __init__(
         self,
         node: ast.AST,
@@ -136,24 +543,38 @@ class _BaseNodeParser:
         internal_modules: Set[Module],
         root_modules: Set[Module],
         is_package: bool,
+        external_import_granularity: str = ExternalImportGranularity.ROOT,
     ) -> None:
         self.node = node
         self.module = module
         self.internal_modules = internal_modules
         self.root_modules = root_modules
         self.module_is_package = is_package
+        self.external_import_granularity = external_import_granularity
+        # The names of any internal modules that the statement imports but which couldn't be
+        # found.
+        self.unresolved_module_names: List[str] = []
 
     def determine_imported_modules(
         self, include_external_packages: bool
-    ) -> Set[Module]:
+    ) -> Set[_ImportedModule]:
         """
-        Return the imported modules in the statement.
+        Return the imported modules in the statement, each with the full name of the object
+        imported from it (or None if the module itself is imported).
         """
         raise NotImplementedError
 
     def _is_internal_module(self, module: Module) -> bool:
         return module.root in self.root_modules
 
+    def _get_external_module(self, module: Module) -> Module:
+        """
+        Return the module to record for an import of the supplied external module.
+        """
+        if self.external_import_granularity == ExternalImportGranularity.FULL:
+            return module
+        return module.root
+
 
 class _ImportNodeParser(_BaseNodeParser):
     """
@@ -164,8 +585,8 @@ class _ImportNodeParser(_BaseNodeParser):
 
     def determine_imported_modules(
         self, include_external_packages: bool
-    ) -> Set[Module]:
-        imported_modules: Set[Module] = set()
+    ) -> Set[_ImportedModule]:
+        imported_modules: Set[_ImportedModule] = set()
 
         assert isinstance(self.node, self.node_class)  # For type checker.
         for alias in self.node.names:
@@ -175,11 +596,11 @@ class _ImportNodeParser(_BaseNodeParser):
                 imported_module = module_from_alias
             else:
                 if include_external_packages:
-                    imported_module = Module(module_from_alias.package_name)
+                    imported_module = self._get_external_module(module_from_alias)
                 else:
                     continue
 
-            imported_modules.add(imported_module)
+            imported_modules.add((imported_module, None))
 
         return imported_modules
 
@@ -193,8 +614,8 @@ class _ImportFromNodeParser(_BaseNodeParser):
 
     def determine_imported_modules(
         self, include_external_packages: bool
-    ) -> Set[Module]:
-        imported_modules: Set[Module] = set()
+    ) -> Set[_ImportedModule]:
+        imported_modules: Set[_ImportedModule] = set()
         assert isinstance(self.node, self.node_class)  # For type checker.
         assert isinstance(self.node.level, int)  # For type checker.
 
@@ -205,8 +626,7 @@ class _ImportFromNodeParser(_BaseNodeParser):
             node_module = Module(self.node.module)
             if not self._is_internal_module(node_module):
                 if include_external_packages:
-                    # Just return the top level package of the external module.
-                    return {Module(node_module.package_name)}
+                    return self._determine_external_imported_modules(node_module)
                 else:
                     return set()
             # Don't include imports of modules outside this package.
@@ -246,10 +666,25 @@ class _ImportFromNodeParser(_BaseNodeParser):
                     f"Could not find {full_module_name} when scanning {self.module}. "
                     "This may be due to a missing __init__.py file in the parent package."
                 )
+                self.unresolved_module_names.append(full_module_name)
             else:
-                imported_modules.add(imported_module)
+                if imported_module.name == full_module_name:
+                    imported_modules.add((imported_module, None))
+                else:
+                    imported_modules.add((imported_module, full_module_name))
         return imported_modules
 
+    def _determine_external_imported_modules(self, node_module: Module) -> Set[_ImportedModule]:
+        if self.external_import_granularity != ExternalImportGranularity.FULL:
+            return {(self._get_external_module(node_module), None)}
+        # We can't tell which of the imported names are modules and which are other objects, so
+        # record each of them as an object imported from the module.
+        assert isinstance(self.node, ast.ImportFrom)  # For type checker.
+        return {
+            (node_module, None if alias.name == "*" else f"{node_module.name}.{alias.name}")
+            for alias in self.node.names
+        }
+
     def _trim_to_internal_module(self, untrimmed_module: Module) -> Module:
         """
         Raises FileNotFoundError if it could not find a valid module.
@@ -269,18 +704,65 @@ class _ImportFromNodeParser(_BaseNodeParser):
                 raise FileNotFoundError()
 
 
+class _ImportModuleCallParser(_BaseNodeParser):
+    """
+    Parser for calls in the form 'importlib.import_module("x")'.
+    """
+
+    node_class = ast.Call
+    is_dynamic = True
+
+    def determine_imported_modules(
+        self, include_external_packages: bool
+    ) -> Set[_ImportedModule]:
+        assert isinstance(self.node, self.node_class)  # For type checker.
+        module_name = _get_string_literal(self.node.args[0])
+        assert module_name  # For type checker.
+        imported_module = Module(module_name)
+
+        if not self._is_internal_module(imported_module):
+            if include_external_packages:
+                return {(self._get_external_module(imported_module), None)}
+            else:
+                return set()
+
+        if imported_module not in self.internal_modules:
+            logger.warning(
+                f"Could not find {module_name} when scanning {self.module}. "
+                "This may be due to a missing __init__.py file in the parent package."
+            )
+            self.unresolved_module_names.append(module_name)
+            return set()
+        return {(imported_module, None)}
+
+
 def _get_node_parser(
     node: ast.AST,
     module: Module,
     internal_modules: Set[Module],
     root_modules: Set[Module],
     is_package: bool,
+    import_module_names: Set[str],
+    external_import_granularity: str = ExternalImportGranularity.ROOT,
 ) -> _BaseNodeParser:
     """
     Return a NodeParser instance for the supplied node.
 
-    Raises NotAnImport if the supplied node is not an import statement.
+    Raises NotAnImport if the supplied node is not an import statement, or a call to
+    importlib.import_module using one of the supplied import_module_names.
     """
+    if isinstance(node, ast.Call):
+        if not _is_literal_import_module_call(node, import_module_names):
+            raise NotAnImport
+        return _ImportModuleCallParser(
+            node=node,
+            module=module,
+            internal_modules=internal_modules,
+            root_modules=root_modules,
+            is_package=is_package,
+            external_import_granularity=external_import_granularity,
+        )
+
     parser_class_map = {
         ast.ImportFrom: _ImportFromNodeParser,
         ast.Import: _ImportNodeParser,
@@ -293,5 +775,407 @@ def _get_node_parser(
                 internal_modules=internal_modules,
                 root_modules=root_modules,
                 is_package=is_package,
+                external_import_granularity=external_import_granularity,
             )
     raise NotAnImport
+
+
+def _find_import_module_names(ast_tree: ast.AST) -> Set[str]:
+    """
+    Return the names through which importlib.import_module may be called in a module.
+
+    For example, if the module contains 'import importlib' and 'from importlib import
+    import_module as im', the result will be {"importlib.import_module", "im"}.
+    """
+    import_module_names: Set[str] = set()
+    for node in ast.walk(ast_tree):
+        if isinstance(node, ast.Import):
+            for alias in node.names:
+                if alias.asname is None and alias.name.split(".")[0] == "importlib":
+                    # Note: 'import importlib.util' also binds the name importlib.
+                    import_module_names.add("importlib.import_module")
+                elif alias.name == "importlib":
+                    import_module_names.add(f"{alias.asname}.import_module")
+        elif isinstance(node, ast.ImportFrom):
+            if node.level == 0 and node.module == "importlib":
+                for alias in node.names:
+                    if alias.name == "import_module":
+                        import_module_names.add(alias.asname or alias.name)
+    return import_module_names
+
+
+def _is_literal_import_module_call(node: ast.Call, import_module_names: Set[str]) -> bool:
+    """
+    Return whether the node is a call to import_module with an absolute module name string.
+    """
+    if _get_dotted_name(node.func) not in import_module_names:
+        return False
+    if not node.args:
+        return False
+    module_name = _get_string_literal(node.args[0])
+    if not module_name:
+        return False
+    # We don't attempt to resolve relative module names (which use the package argument).
+    return not module_name.startswith(".")
+
+
+def _get_dotted_name(node: ast.AST) -> Optional[str]:
+    """
+    Return the dotted name of a Name or Attribute node, e.g. 'importlib.import_module'.
+    """
+    if isinstance(node, ast.Name):
+        return node.id
+    if isinstance(node, ast.Attribute):
+        value_name = _get_dotted_name(node.value)
+        if value_name:
+            return f"{value_name}.{node.attr}"
+    return None
+
+
+def _get_string_literal(node: ast.AST) -> Optional[str]:
+    """
+    Return the value of a string literal node, or None if the node isn't a string literal.
+    """
+    if sys.version_info >= (3, 8):
+        if isinstance(node, ast.Constant) and isinstance(node.value, str):
+            return node.value
+    elif isinstance(node, ast.Str):
+        return node.s
+    return None
+
+
+def _find_literal_collections(ast_tree: ast.Module) -> Dict[str, List[str]]:
+    """
+    Return the strings in any lists, tuples, sets or dictionaries of string literals assigned to
+    names at the top level of the module. For dictionaries, both the keys and values are
+    included.
+    """
+    literal_collections: Dict[str, List[str]] = {}
+    for statement in ast_tree.body:
+        if isinstance(statement, ast.Assign):
+            targets, value = statement.targets, statement.value
+        elif isinstance(statement, ast.AnnAssign) and statement.value is not None:
+            targets, value = [statement.target], statement.value
+        else:
+            continue
+        if isinstance(value, ast.Dict):
+            elements = [key for key in value.keys if key is not None] + list(value.values)
+        elif isinstance(value, (ast.List, ast.Tuple, ast.Set)):
+            elements = list(value.elts)
+        else:
+            continue
+        strings = [_get_string_literal(element) for element in elements]
+        for target in targets:
+            if isinstance(target, ast.Name):
+                literal_collections[target.id] = [s for s in strings if s is not None]
+    return literal_collections
+
+
+def _render_string_template(
+    node: ast.AST, substitutions: Dict[str, str], variable_value: str
+) -> Optional[str]:
+    """
+    Return the value of a string expression built from literals, names and subscripts, for
+    example f"{__name__}.{name}". Names in the substitutions are replaced with their values;
+    any other names (and subscripts) are replaced with the variable value. Returns None if the
+    expression is of any other form.
+    """
+    literal = _get_string_literal(node)
+    if literal is not None:
+        return literal
+    if isinstance(node, ast.Name):
+        return substitutions.get(node.id, variable_value)
+    if isinstance(node, ast.Subscript):
+        return variable_value
+    if isinstance(node, ast.BinOp) and isinstance(node.op, ast.Add):
+        left = _render_string_template(node.left, substitutions, variable_value)
+        right = _render_string_template(node.right, substitutions, variable_value)
+        return None if left is None or right is None else left + right
+    if isinstance(node, ast.JoinedStr):
+        parts = []
+        for value in node.values:
+            if isinstance(value, ast.FormattedValue):
+                part = _render_string_template(value.value, substitutions, variable_value)
+            else:
+                part = _get_string_literal(value)
+            if part is None:
+                return None
+            parts.append(part)
+        return "".join(parts)
+    return None
+
+
+def _parse_ignore_directive(line_contents: str) -> Optional[Tuple[str, ...]]:
+    """
+    Parse any '# grimp: ignore' comment in the line.
+
+    Returns None if there is no such comment, an empty tuple for a bare '# grimp: ignore', or the
+    names listed in a '# grimp: ignore[name-one, name-two]' comment.
+    """
+    match = _IGNORE_DIRECTIVE_REGEX.search(line_contents)
+    if not match:
+        return None
+    names = match.group("names") or ""
+    return tuple(name.strip() for name in names.split(",") if name.strip())
+
+
+def _parse_node_ignore_directive(
+    node: ast.AST, module_lines: List[str]
+) -> Optional[Tuple[str, ...]]:
+    """
+    Parse any '# grimp: ignore' comment on the lines spanned by the node.
+
+    For a statement spread over several lines, e.g. a parenthesised 'from x import (...)', the
+    comment may go on any of its lines.
+    """
+    end_line_number = _get_end_line_number(node, module_lines)
+    for line_contents in module_lines[node.lineno - 1 : end_line_number]:
+        ignored_for = _parse_ignore_directive(line_contents)
+        if ignored_for is not None:
+            return ignored_for
+    return None
+
+
+def _find_cython_imports(
+    module_lines: List[str],
+) -> Iterator[Tuple[int, int, int, str, str, bool]]:
+    """
+    Find the import statements (including cimports) in the lines of a Cython file.
+
+    Yields, for each statement, its first and last line numbers, the column at which it starts,
+    its Python equivalent (e.g. 'from foo import bar' for 'from foo cimport bar'), its scope
+    (see ImportScope) and whether it is a cimport.
+
+    This works line by line rather than by parsing the file, so the scope is determined from the
+    indentation of the enclosing function and class definitions.
+    """
+    # The indentation and scope of each function or class definition enclosing the current line.
+    enclosing_blocks: List[Tuple[int, str]] = []
+    is_in_multiline_string = False
+    line_index = 0
+    while line_index < len(module_lines):
+        line = module_lines[line_index]
+        line_index += 1
+
+        # Skip the contents of docstrings and other triple-quoted strings.
+        triple_quote_count = line.count('"""') + line.count("'''")
+        if is_in_multiline_string or triple_quote_count % 2:
+            if triple_quote_count % 2:
+                is_in_multiline_string = not is_in_multiline_string
+            continue
+
+        code = line.split("#")[0].rstrip()
+        stripped_code = code.lstrip()
+        if not stripped_code:
+            continue
+        indentation = len(code) - len(stripped_code)
+        while enclosing_blocks and enclosing_blocks[-1][0] >= indentation:
+            enclosing_blocks.pop()
+        scope = enclosing_blocks[-1][1] if enclosing_blocks else ImportScope.MODULE
+
+        if _CYTHON_IMPORT_REGEX.match(stripped_code):
+            line_number = line_index
+            statement = stripped_code
+            # Join any continuation lines.
+            while (
+                statement.endswith("\\") or statement.count("(") > statement.count(")")
+            ) and line_index < len(module_lines):
+                continuation = module_lines[line_index].split("#")[0].strip()
+                if statement.endswith("\\"):
+                    statement = statement[:-1]
+                statement = f"{statement} {continuation}"
+                line_index += 1
+            is_cimport = bool(_CYTHON_CIMPORT_REGEX.search(statement))
+            yield (
+                line_number,
+                line_index,
+                indentation,
+                _CYTHON_CIMPORT_REGEX.sub("import", statement, count=1),
+                scope,
+                is_cimport,
+            )
+        elif _CYTHON_CLASS_REGEX.match(stripped_code):
+            enclosing_blocks.append((indentation, ImportScope.CLASS))
+        elif _CYTHON_FUNCTION_REGEX.match(stripped_code):
+            enclosing_blocks.append((indentation, ImportScope.FUNCTION))
+
+
+def _get_end_line_number(node: ast.AST, module_lines: List[str]) -> int:
+    """
+    Return the number of the last line of the statement (or call) represented by the node.
+    """
+    end_line_number = getattr(node, "end_lineno", None)
+    if end_line_number is not None:
+        return end_line_number
+
+    # Versions of Python before 3.8 don't record where nodes end, so find the end of the
+    # statement by following any open brackets and line continuations.
+    end_line_number = node.lineno
+    code = module_lines[node.lineno - 1][node.col_offset :].split("#")[0].rstrip()
+    bracket_depth = code.count("(") - code.count(")")
+    while (bracket_depth > 0 or code.endswith("\\")) and end_line_number < len(module_lines):
+        code = module_lines[end_line_number].split("#")[0].rstrip()
+        bracket_depth += code.count("(") - code.count(")")
+        end_line_number += 1
+    return end_line_number
+
+
+def _find_type_checking_nodes(ast_tree: ast.AST, guard_names: Set[str]) -> Set[ast.AST]:
+    """
+    Return all the nodes that are within the body of an 'if' statement whose condition is one
+    of the supplied guard names, e.g. 'if TYPE_CHECKING:'.
+    """
+    type_checking_nodes: Set[ast.AST] = set()
+    for node in ast.walk(ast_tree):
+        if isinstance(node, ast.If) and _get_dotted_name(node.test) in guard_names:
+            for statement in node.body:
+                type_checking_nodes.update(ast.walk(statement))
+    return type_checking_nodes
+
+
+def _find_conditions(
+    ast_tree: ast.AST, module_lines: List[str], condition_names: FrozenSet[str]
+) -> Dict[ast.AST, str]:
+    """
+    Return the nodes that are within an 'if' statement whose condition refers to any of the
+    supplied names (e.g. 'if sys.platform == "win32":' for 'sys.platform'), mapped to the text of
+    the condition. Nodes in the 'else' branch are mapped to the negated condition.
+    """
+    conditions: Dict[ast.AST, str] = {}
+    # Outer statements are walked first, so any nested conditions take precedence.
+    for node in ast.walk(ast_tree):
+        if not isinstance(node, ast.If):
+            continue
+        if not any(_get_dotted_name(n) in condition_names for n in ast.walk(node.test)):
+            continue
+        condition = _get_condition_text(node, module_lines)
+        for branch, branch_condition in (
+            (node.body, condition),
+            (node.orelse, f"not ({condition})"),
+        ):
+            for statement in branch:
+                for descendant in ast.walk(statement):
+                    conditions[descendant] = branch_condition
+    return conditions
+
+
+def _get_condition_text(node: ast.If, module_lines: List[str]) -> str:
+    """
+    Return the text of the condition of an 'if' (or 'elif') statement, as it appears on the
+    statement's first line.
+    """
+    line = module_lines[node.lineno - 1].split("#")[0].strip()
+    condition = re.sub(r"^(?:el)?if\b", "", line).strip()
+    return condition[:-1].strip() if condition.endswith(":") else condition
+
+
+def _find_optional_nodes(ast_tree: ast.AST) -> Set[ast.AST]:
+    """
+    Return all the nodes that are within the body of a 'try' statement with a handler for
+    ImportError, or of a 'with' statement that suppresses it using contextlib.suppress.
+    """
+    optional_nodes: Set[ast.AST] = set()
+    for node in ast.walk(ast_tree):
+        if isinstance(node, ast.Try):
+            is_optional = any(_catches_import_error(handler.type) for handler in node.handlers)
+        elif isinstance(node, ast.With):
+            is_optional = any(_suppresses_import_error(item.context_expr) for item in node.items)
+        else:
+            continue
+        if is_optional:
+            for statement in node.body:
+                optional_nodes.update(ast.walk(statement))
+    return optional_nodes
+
+
+def _catches_import_error(exception_node: Optional[ast.AST]) -> bool:
+    """
+    Return whether the exception (or tuple of exceptions) in an 'except' clause includes
+    ImportError. A bare 'except:' catches everything.
+    """
+    if exception_node is None:
+        return True
+    if isinstance(exception_node, ast.Tuple):
+        return any(_catches_import_error(element) for element in exception_node.elts)
+    return _get_dotted_name(exception_node) in IMPORT_ERROR_NAMES
+
+
+def _suppresses_import_error(node: ast.AST) -> bool:
+    """
+    Return whether the node is a call such as 'contextlib.suppress(ImportError)'.
+    """
+    return (
+        isinstance(node, ast.Call)
+        and _get_dotted_name(node.func) in ("contextlib.suppress", "suppress")
+        and any(
+            _catches_import_error(argument)
+            for argument in node.args
+            if not isinstance(argument, ast.Starred)
+        )
+    )
+
+
+def _walk_with_scope(ast_tree: ast.AST) -> Iterator[Tuple[ast.AST, str]]:
+    """
+    Recursively yield all the nodes in the tree (in no particular order), along with the scope
+    in which each one occurs (see ImportScope).
+    """
+    # Like ast.walk, this avoids recursion so that deeply nested code can't hit the recursion
+    # limit.
+    nodes_to_visit = [(ast_tree, ImportScope.MODULE)]
+    while nodes_to_visit:
+        node, scope = nodes_to_visit.pop()
+        yield node, scope
+
+        if isinstance(node, (ast.FunctionDef, ast.AsyncFunctionDef, ast.Lambda)):
+            child_scope = ImportScope.FUNCTION
+        elif isinstance(node, ast.ClassDef):
+            child_scope = ImportScope.CLASS
+        else:
+            child_scope = scope
+        for child in ast.iter_child_nodes(node):
+            nodes_to_visit.append((child, child_scope))
+
+
+def _find_all_names(ast_tree: ast.Module) -> Optional[List[str]]:
+    """
+    Return the names in the module's __all__, or None if they can't be determined statically.
+
+    Supports assigning (and adding to) __all__ at the top level of the module, using lists or
+    tuples of string literals.
+    """
+    all_names: Optional[List[str]] = None
+    for node in ast_tree.body:
+        if isinstance(node, ast.Assign):
+            if not any(_get_dotted_name(target) == "__all__" for target in node.targets):
+                continue
+            all_names = _get_string_literals(node.value)
+            if all_names is None:
+                return None
+        elif isinstance(node, ast.AnnAssign) and _get_dotted_name(node.target) == "__all__":
+            if node.value is None:
+                continue
+            all_names = _get_string_literals(node.value)
+            if all_names is None:
+                return None
+        elif isinstance(node, ast.AugAssign) and _get_dotted_name(node.target) == "__all__":
+            additional_names = _get_string_literals(node.value)
+            if all_names is None or additional_names is None:
+                return None
+            all_names = all_names + additional_names
+    return all_names
+
+
+def _get_string_literals(node: ast.AST) -> Optional[List[str]]:
+    """
+    Return the values of a list or tuple of string literals, or None if the node isn't one.
+    """
+    if not isinstance(node, (ast.List, ast.Tuple)):
+        return None
+    values = []
+    for element in node.elts:
+        value = _get_string_literal(element)
+        if value is None:
+            return None
+        values.append(value)
+    return values
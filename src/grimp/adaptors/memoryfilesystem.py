@@ -0,0 +1,85 @@
+import os
+import time
+from typing import Dict, List, Optional, Tuple
+
+from grimp.application.ports.filesystem import AbstractFileSystem
+
+
+class InMemoryFileSystem(AbstractFileSystem):
+    """
+    File system whose files are held in memory, rather than on disk.
+
+    Directories are implied by the files within them: adding '/mypackage/foo/__init__.py' also
+    creates the directories '/mypackage' and '/mypackage/foo'. Each file's modification time is
+    the time at which it was added.
+    """
+
+    def __init__(self, files: Optional[Dict[str, str]] = None) -> None:
+        """
+        Args:
+            - files: the contents of each file, keyed by its full filename.
+        """
+        self._files: Dict[str, str] = {}
+        self._mtimes: Dict[str, float] = {}
+        self._subdirectories: Dict[str, List[str]] = {}
+        self._filenames: Dict[str, List[str]] = {}
+        for filename, contents in (files or {}).items():
+            self.add_file(filename, contents)
+
+    def add_file(self, filename: str, contents: str) -> None:
+        self._files[filename] = contents
+        self._mtimes[filename] = time.time()
+        directory, name = self.split(filename)
+        self._register_directory(directory)
+        if name not in self._filenames[directory]:
+            self._filenames[directory].append(name)
+
+    def dirname(self, filename: str) -> str:
+        return os.path.dirname(filename)
+
+    def walk(self, directory_name, follow_symlinks: bool = False):
+        # There are no symbolic links, so follow_symlinks has no effect.
+        if directory_name not in self._subdirectories:
+            return
+        subdirectories = list(self._subdirectories[directory_name])
+        # As with os.walk, callers may remove directories from the list to avoid walking them.
+        yield directory_name, subdirectories, list(self._filenames[directory_name])
+        for subdirectory in subdirectories:
+            yield from self.walk(self.join(directory_name, subdirectory))
+
+    def join(self, *components: str) -> str:
+        return os.path.join(*components)
+
+    def split(self, file_name: str) -> Tuple[str, str]:
+        return os.path.split(file_name)
+
+    def realpath(self, path: str) -> str:
+        return path
+
+    def read(self, file_name: str) -> str:
+        try:
+            return self._files[file_name]
+        except KeyError:
+            raise FileNotFoundError(file_name)
+
+    def getsize(self, file_name: str) -> int:
+        return len(self.read(file_name).encode("utf-8"))
+
+    def getmtime(self, file_name: str) -> float:
+        try:
+            return self._mtimes[file_name]
+        except KeyError:
+            raise FileNotFoundError(file_name)
+
+    def exists(self, file_name: str) -> bool:
+        return file_name in self._files
+
+    def _register_directory(self, directory: str) -> None:
+        if directory in self._subdirectories:
+            return
+        self._subdirectories[directory] = []
+        self._filenames[directory] = []
+        parent, name = self.split(directory)
+        if name:
+            self._register_directory(parent)
+            self._subdirectories[parent].append(name)
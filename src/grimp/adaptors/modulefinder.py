@@ -1,82 +1,350 @@
 import logging
 import os
-from typing import Iterable, List
+from typing import Dict, Iterable, List, Optional, Set
 
+from grimp import exceptions
+from grimp.adaptors.gitignore import GitIgnoreRules
 from grimp.application.ports import modulefinder
 from grimp.application.ports.filesystem import AbstractFileSystem
 from grimp.domain.valueobjects import Module
 
 logger = logging.getLogger(__name__)
 
+# The extensions of the files that define modules, when including Cython.
+SOURCE_FILE_EXTENSIONS = (".py", ".pyx", ".pxd")
+
+# The names of directories that are skipped by default, unless they are packages, as they are
+# likely to contain build artifacts or installed dependencies rather than modules.
+DEFAULT_SKIPPED_DIRECTORY_NAMES = frozenset(
+    {".venv", "venv", "node_modules", "build", "dist", ".tox", "__pycache__"}
+)
+
+# The number of lines at the start of each file that are searched for the generated file marker.
+GENERATED_FILE_MARKER_LINES = 10
+
 
 class ModuleFinder(modulefinder.AbstractModuleFinder):
     def find_modules(
-        self, package_name: str, package_directory: str, file_system: AbstractFileSystem
-    ) -> Iterable[Module]:
+        self,
+        package_name: str,
+        package_directory: str,
+        file_system: AbstractFileSystem,
+        namespace_packages: bool = False,
+        follow_symlinks: bool = False,
+        respect_gitignore: bool = False,
+        include_cython: bool = False,
+        generated_file_marker: Optional[str] = None,
+        additional_module_extensions: Iterable[str] = (),
+        skipped_directory_names: Optional[Iterable[str]] = None,
+    ) -> modulefinder.FoundModules:
+        # The finder may be shared, so each search keeps its state separately.
+        search = _ModuleSearch(
+            file_system=file_system,
+            additional_module_extensions=additional_module_extensions,
+            skipped_directory_names=skipped_directory_names,
+        )
+        return search.find_modules(
+            package_name,
+            package_directory,
+            namespace_packages,
+            follow_symlinks,
+            respect_gitignore,
+            include_cython,
+            generated_file_marker,
+        )
+
+
+class _ModuleSearch:
+    """
+    A single search of a package for its modules.
+    """
+
+    def __init__(
+        self,
+        file_system: AbstractFileSystem,
+        additional_module_extensions: Iterable[str],
+        skipped_directory_names: Optional[Iterable[str]],
+    ) -> None:
         self.file_system = file_system
+        self.additional_module_extensions = tuple(additional_module_extensions)
+        self.skipped_directory_names = (
+            DEFAULT_SKIPPED_DIRECTORY_NAMES
+            if skipped_directory_names is None
+            else frozenset(skipped_directory_names)
+        )
+        self.warnings: List[exceptions.GrimpWarning] = []
+        self.ignored_files: List[Dict[str, str]] = []
 
+    def find_modules(
+        self,
+        package_name: str,
+        package_directory: str,
+        namespace_packages: bool,
+        follow_symlinks: bool,
+        respect_gitignore: bool,
+        include_cython: bool,
+        generated_file_marker: Optional[str],
+    ) -> modulefinder.FoundModules:
         modules: List[Module] = []
+        module_names: Set[str] = set()
+        module_filenames: Dict[Module, str] = {}
 
-        for module_filename in self._get_python_files_inside_package(package_directory):
+        for module_filename in self._get_python_files_inside_package(
+            package_directory,
+            namespace_packages,
+            follow_symlinks,
+            respect_gitignore,
+            include_cython,
+            generated_file_marker,
+        ):
             module_name = self._module_name_from_filename(
-                module_filename, package_directory
+                package_name, module_filename, package_directory
             )
-            modules.append(Module(module_name))
+            # With Cython, a module may be defined by more than one file (e.g. foo.pyx and
+            # foo.pxd).
+            if module_name not in module_names:
+                module_names.add(module_name)
+                modules.append(Module(module_name))
+                module_filenames[Module(module_name)] = module_filename
 
-        return modules
+        return modulefinder.FoundModules(
+            modules,
+            module_filenames=module_filenames,
+            warnings=self.warnings,
+            ignored_files=self.ignored_files,
+        )
 
-    def _get_python_files_inside_package(self, directory: str) -> Iterable[str]:
+    def _get_python_files_inside_package(
+        self,
+        directory: str,
+        namespace_packages: bool,
+        follow_symlinks: bool,
+        respect_gitignore: bool,
+        include_cython: bool,
+        generated_file_marker: Optional[str],
+    ) -> Iterable[str]:
         """
         Get a list of Python files within the supplied package directory.
          Return:
             Generator of Python file names.
         """
-        for dirpath, dirs, files in self.file_system.walk(directory):
+        visited_directories: Set[str] = set()
+        gitignore_rules = (
+            self._read_ancestor_gitignore_rules(directory) if respect_gitignore else None
+        )
+
+        for dirpath, dirs, files in self.file_system.walk(
+            directory, follow_symlinks=follow_symlinks
+        ):
+            if follow_symlinks:
+                # Only search each directory once. This protects against symlink cycles,
+                # which would otherwise make us walk forever.
+                canonical_dirpath = self.file_system.realpath(dirpath)
+                if canonical_dirpath in visited_directories:
+                    for d in list(dirs):
+                        dirs.remove(d)
+                    continue
+                visited_directories.add(canonical_dirpath)
+
             # Don't include directories that aren't Python packages,
-            # nor their subdirectories.
-            if "__init__.py" not in files:
+            # nor their subdirectories (unless we're treating them as namespace portions).
+            if "__init__.py" not in files and not namespace_packages:
+                if any(self._is_python_file(f, include_cython) for f in files):
+                    self._record_ignored_file(
+                        dirpath, "Directory has no __init__.py file, so is not a package."
+                    )
                 for d in list(dirs):
                     dirs.remove(d)
                 continue
 
+            if gitignore_rules is not None:
+                if ".gitignore" in files:
+                    gitignore_rules.add_rules(
+                        dirpath,
+                        self.file_system.read(self.file_system.join(dirpath, ".gitignore")),
+                    )
+                dirs_to_remove = [
+                    d
+                    for d in dirs
+                    if gitignore_rules.is_ignored(
+                        self.file_system.join(dirpath, d), is_directory=True
+                    )
+                ]
+                for d in dirs_to_remove:
+                    self._record_ignored_file(
+                        self.file_system.join(dirpath, d), "Ignored by .gitignore."
+                    )
+                    dirs.remove(d)
+
             # Don't include hidden directories.
             dirs_to_remove = [d for d in dirs if self._should_ignore_dir(d)]
             for d in dirs_to_remove:
+                hidden_directory = self.file_system.join(dirpath, d)
+                if self.file_system.exists(self.file_system.join(hidden_directory, "__init__.py")):
+                    self._record_ignored_file(hidden_directory, "Hidden directory.")
+                dirs.remove(d)
+
+            # Don't include build directories or virtual environments. A directory with one of the
+            # skipped names is still included if it's a package, as it's then a real subpackage;
+            # otherwise it would only have been included as a namespace portion.
+            for d in list(dirs):
+                skipped_directory = self.file_system.join(dirpath, d)
+                if self.file_system.exists(self.file_system.join(skipped_directory, "pyvenv.cfg")):
+                    self._record_ignored_file(skipped_directory, "Virtual environment.")
+                    dirs.remove(d)
+                elif (
+                    namespace_packages
+                    and d in self.skipped_directory_names
+                    and not self.file_system.exists(
+                        self.file_system.join(skipped_directory, "__init__.py")
+                    )
+                ):
+                    self._record_ignored_file(
+                        skipped_directory, "Directory is in the skipped directory names."
+                    )
+                    dirs.remove(d)
+
+            # Directories with dots in their names can't be imported as packages.
+            dirs_to_remove = [d for d in dirs if "." in d]
+            for d in dirs_to_remove:
+                dotted_directory = self.file_system.join(dirpath, d)
+                if self.file_system.exists(self.file_system.join(dotted_directory, "__init__.py")):
+                    self._warn_about_dotted_filename(dotted_directory)
                 dirs.remove(d)
 
             for filename in files:
-                if self._is_python_file(filename):
-                    yield self.file_system.join(dirpath, filename)
+                if filename.startswith(".") and self._is_python_file(
+                    filename[1:], include_cython
+                ):
+                    self._record_ignored_file(
+                        self.file_system.join(dirpath, filename), "Hidden file."
+                    )
+                if self._is_python_file(filename, include_cython):
+                    filename_and_path = self.file_system.join(dirpath, filename)
+                    if gitignore_rules is not None and gitignore_rules.is_ignored(
+                        filename_and_path, is_directory=False
+                    ):
+                        self._record_ignored_file(filename_and_path, "Ignored by .gitignore.")
+                        continue
+                    if "." in self._strip_extension(filename):
+                        self._warn_about_dotted_filename(filename_and_path)
+                        continue
+                    if generated_file_marker and self._is_generated_file(
+                        filename_and_path, generated_file_marker
+                    ):
+                        self._record_ignored_file(
+                            filename_and_path,
+                            f"Generated file, as it contains {generated_file_marker!r} in its "
+                            f"first {GENERATED_FILE_MARKER_LINES} lines.",
+                        )
+                        continue
+                    yield filename_and_path
+
+    def _is_generated_file(self, filename: str, generated_file_marker: str) -> bool:
+        try:
+            contents = self.file_system.read(filename)
+        except (UnicodeDecodeError, SyntaxError):
+            # The file can't be decoded, which will be reported when it's scanned.
+            return False
+        first_lines = contents.splitlines()[:GENERATED_FILE_MARKER_LINES]
+        return any(generated_file_marker in line for line in first_lines)
+
+    def _read_ancestor_gitignore_rules(self, directory: str) -> GitIgnoreRules:
+        """
+        Return the rules from any .gitignore files in the parent directories of the supplied
+        directory.
+
+        As with git, only the directories up to the root of the repository (the nearest one
+        containing .git) are searched. If the directory isn't in a repository, all its parent
+        directories are searched.
+        """
+        ancestor_directories = []
+        current_directory = directory
+        while not self._is_repository_root(current_directory):
+            parent_directory = self.file_system.dirname(current_directory)
+            if not parent_directory or parent_directory == current_directory:
+                break
+            ancestor_directories.append(parent_directory)
+            current_directory = parent_directory
+
+        gitignore_rules = GitIgnoreRules()
+        # Add the outermost rules first, as the innermost ones take precedence.
+        for ancestor_directory in reversed(ancestor_directories):
+            gitignore_filename = self.file_system.join(ancestor_directory, ".gitignore")
+            if self.file_system.exists(gitignore_filename):
+                gitignore_rules.add_rules(
+                    ancestor_directory, self.file_system.read(gitignore_filename)
+                )
+        return gitignore_rules
+
+    def _is_repository_root(self, directory: str) -> bool:
+        # The file system only reports whether files exist, so look for the HEAD file within the
+        # .git directory. In a worktree or submodule, .git is itself a file.
+        return self.file_system.exists(
+            self.file_system.join(directory, ".git", "HEAD")
+        ) or self.file_system.exists(self.file_system.join(directory, ".git"))
+
+    def _record_ignored_file(self, filename: str, reason: str) -> None:
+        logger.debug(f"Ignoring {filename}: {reason}")
+        self.ignored_files.append({"filename": filename, "reason": reason})
+
+    def _warn_about_dotted_filename(self, filename_and_path: str) -> None:
+        logger.warning(f"Ignoring {filename_and_path}, as its name contains a dot.")
+        self._record_ignored_file(
+            filename_and_path, "Name contains a dot, so it can't be imported as a module."
+        )
+        self.warnings.append(
+            exceptions.DottedFilenameWarning(
+                f"Ignored {filename_and_path}, as its name contains a dot so it can't be "
+                "imported as a module."
+            )
+        )
 
     def _should_ignore_dir(self, directory: str) -> bool:
         # TODO: make this configurable.
         # Skip adding directories that are hidden.
         return directory.startswith(".")
 
-    def _is_python_file(self, filename: str) -> bool:
+    def _is_python_file(self, filename: str, include_cython: bool = False) -> bool:
         """
         Given a filename, return whether it's a Python file.
 
         Args:
             filename (str): the filename, excluding the path.
+            include_cython (bool): whether to treat Cython files as Python files.
         Returns:
             bool: whether it's a Python file.
         """
-        return not filename.startswith(".") and filename.endswith(".py")
+        extensions = (
+            SOURCE_FILE_EXTENSIONS if include_cython else (".py",)
+        ) + self.additional_module_extensions
+        return not filename.startswith(".") and filename.endswith(extensions)
+
+    def _strip_extension(self, filename: str) -> str:
+        """
+        Return the filename without its extension, which may itself contain dots (e.g. '.py.in').
+        """
+        for extension in self.additional_module_extensions:
+            if filename.endswith(extension):
+                return filename[: -len(extension)]
+        return os.path.splitext(filename)[0]
 
     def _module_name_from_filename(
-        self, filename_and_path: str, package_directory: str
+        self, package_name: str, filename_and_path: str, package_directory: str
     ) -> str:
         """
         Args:
+            package_name (string) - the importable name of the top level Python package, which
+                                    may differ from the name of its directory.
             filename_and_path (string) - the full name of the Python file.
             package_directory (string) - the full path of the top level Python package directory.
          Returns:
             Absolute module name for importing (string).
         """
-        container_directory, package_name = self.file_system.split(package_directory)
         internal_filename_and_path = filename_and_path[len(package_directory) :]
-        internal_filename_and_path_without_extension = internal_filename_and_path[1:-3]
+        internal_filename_and_path_without_extension = self._strip_extension(
+            internal_filename_and_path[1:]
+        )
         components = [
             package_name
         ] + internal_filename_and_path_without_extension.split(os.sep)
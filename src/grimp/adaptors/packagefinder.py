@@ -1,6 +1,7 @@
 import importlib.util
 import logging
 import sys
+from typing import List, Optional
 
 from grimp import exceptions
 from grimp.application.ports.filesystem import AbstractFileSystem
@@ -13,20 +14,65 @@ class ImportLibPackageFinder(AbstractPackageFinder):
     def determine_package_directory(
         self, package_name: str, file_system: AbstractFileSystem
     ) -> str:
-        # TODO - do we need to add the current working directory here?
+        [package_directory] = self.determine_package_directories(package_name, file_system)
+        return package_directory
+
+    def determine_package_directories(
+        self,
+        package_name: str,
+        file_system: AbstractFileSystem,
+        namespace_packages: bool = False,
+    ) -> List[str]:
         # Attempt to locate the package file.
         package_filename = importlib.util.find_spec(package_name)
         if not package_filename:
+            src_layout_directory = self._find_src_layout_directory(package_name, file_system)
+            if src_layout_directory:
+                return [src_layout_directory]
             logger.debug("sys.path: {}".format(sys.path))
             raise ValueError(
                 "Could not find package '{}' in your Python path.".format(package_name)
             )
 
         if package_filename.has_location and package_filename.origin:
-            return file_system.dirname(package_filename.origin)
+            package_directory = file_system.dirname(package_filename.origin)
+            # Strict editable installs (PEP 660) import the package from a tree of links to the
+            # source files. If the __init__.py is such a link, use the real source directory.
+            # This may be named differently from the package, and includes any files that the
+            # install left out of the link tree.
+            real_package_directory = file_system.dirname(
+                file_system.realpath(package_filename.origin)
+            )
+            if real_package_directory != file_system.realpath(package_directory):
+                return [real_package_directory]
+            return [package_directory]
+
+        if namespace_packages and package_filename.submodule_search_locations:
+            # Each location is a separate portion of the namespace package.
+            return list(package_filename.submodule_search_locations)
 
         raise exceptions.NamespacePackageEncountered(
             f"Package {package_name} appears to be a 'namespace package' (see PEP 420), "
             "which is not currently supported. If this is not deliberate, adding an __init__.py "
             "file should fix the problem."
         )
+
+    def _find_src_layout_directory(
+        self, package_name: str, file_system: AbstractFileSystem
+    ) -> Optional[str]:
+        """
+        Return the directory of the package if it is in the 'src' directory of a Python path
+        entry (i.e. a project with a src layout that isn't installed).
+
+        The empty entry, which stands for the current working directory, is skipped so that
+        the result doesn't depend on where the process is run from.
+        """
+        if "." in package_name:
+            return None
+        for path_entry in sys.path:
+            if not path_entry:
+                continue
+            package_directory = file_system.join(path_entry, "src", package_name)
+            if file_system.exists(file_system.join(package_directory, "__init__.py")):
+                return package_directory
+        return None
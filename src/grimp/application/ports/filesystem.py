@@ -17,12 +17,16 @@ class AbstractFileSystem(abc.ABC):
         raise NotImplementedError
 
     @abc.abstractmethod
-    def walk(self, directory_name):
+    def walk(self, directory_name, follow_symlinks: bool = False):
         """
         Given a directory, walk the file system recursively.
 
         For each directory in the tree rooted at directory top (including top itself),
         it yields a 3-tuple (dirpath, dirnames, filenames).
+
+        If follow_symlinks is True, symlinked directories will be walked too. Note that this
+        can lead to infinite recursion if a link points to one of its own ancestors: callers
+        are responsible for guarding against this.
         """
         raise NotImplementedError
 
@@ -42,6 +46,13 @@ class AbstractFileSystem(abc.ABC):
         """
         raise NotImplementedError
 
+    @abc.abstractmethod
+    def realpath(self, path: str) -> str:
+        """
+        Return the canonical path of the supplied path, resolving any symbolic links.
+        """
+        raise NotImplementedError
+
     @abc.abstractmethod
     def read(self, file_name: str) -> str:
         """
@@ -49,6 +60,20 @@ class AbstractFileSystem(abc.ABC):
         """
         raise NotImplementedError
 
+    @abc.abstractmethod
+    def getsize(self, file_name: str) -> int:
+        """
+        Return the size of a file, in bytes.
+        """
+        raise NotImplementedError
+
+    @abc.abstractmethod
+    def getmtime(self, file_name: str) -> float:
+        """
+        Return the time at which a file was last modified, in seconds since the epoch.
+        """
+        raise NotImplementedError
+
     @abc.abstractmethod
     def exists(self, file_name: str) -> bool:
         """
@@ -1,5 +1,7 @@
 import abc
-from typing import Dict, Iterator, List, Optional, Set, Tuple, Union
+from typing import Any, Dict, Iterable, Iterator, List, Optional, Set, TextIO, Tuple, Union
+
+from grimp.domain.valueobjects import ImportScope
 
 
 class AbstractImportGraph(abc.ABC):
@@ -19,7 +21,9 @@ class AbstractImportGraph(abc.ABC):
         raise NotImplementedError
 
     @abc.abstractmethod
-    def add_module(self, module: str, is_squashed: bool = False) -> None:
+    def add_module(
+        self, module: str, is_squashed: bool = False, external_kind: Optional[str] = None
+    ) -> None:
         """
         Add a module to the graph.
 
@@ -28,6 +32,9 @@ class AbstractImportGraph(abc.ABC):
         Using squashed modules allows you to simplify some parts of the graph, for example if you
         want to include an external package in the graph but don't care about all the dependencies
         within that package.
+
+        If external_kind is supplied, the module is marked as being external to the packages under
+        analysis, of the given kind (see ExternalModuleKind).
         """
         raise NotImplementedError
 
@@ -69,10 +76,40 @@ class AbstractImportGraph(abc.ABC):
         imported: str,
         line_number: Optional[int] = None,
         line_contents: Optional[str] = None,
+        is_dynamic: bool = False,
+        scope: str = ImportScope.MODULE,
+        imported_object: Optional[str] = None,
+        is_type_checking: bool = False,
+        ignored_for: Tuple[str, ...] = (),
+        is_cimport: bool = False,
+        column: Optional[int] = None,
+        end_line_number: Optional[int] = None,
+        platform_condition: Optional[str] = None,
+        is_optional: bool = False,
+        is_lazy: bool = False,
+        version_condition: Optional[str] = None,
     ) -> None:
         """
         Add a direct import between two modules to the graph. If the modules are not already
         present, they will be added to the graph.
+
+        The line_number, line_contents, is_dynamic, scope, imported_object, is_type_checking,
+        ignored_for, is_cimport, column, end_line_number, platform_condition, is_optional, is_lazy
+        and version_condition arguments are stored as import details (see get_import_details).
+        is_dynamic indicates whether the import happens via a call to importlib.import_module,
+        rather than an import statement. scope is one of 'module', 'function' or 'class', depending
+        on where the import occurs. imported_object is the full name of any object (e.g. a
+        function) imported from the imported module. is_type_checking indicates whether the import
+        is guarded by a type checking constant such as TYPE_CHECKING. ignored_for contains the
+        names from any '# grimp: ignore[...]' comment on the line. is_cimport indicates whether the
+        import is a Cython cimport. column is the (zero-based) column at which the import statement
+        starts, and end_line_number is the last line of the statement, which may span several
+        lines. platform_condition is the condition of any 'if' statement that makes the import
+        platform-specific, such as 'sys.platform == "win32"'. is_optional indicates whether a
+        failure to import is caught, for example by 'except ImportError:'. is_lazy indicates
+        whether the import is made lazily, by a module-level __getattr__ function.
+        version_condition is the condition of any 'if' statement that makes the import depend on
+        the Python version, such as 'sys.version_info >= (3, 8)'.
         """
         raise NotImplementedError
 
@@ -90,6 +127,110 @@ class AbstractImportGraph(abc.ABC):
         """
         raise NotImplementedError
 
+    @abc.abstractmethod
+    def memory_usage(self) -> Dict[str, int]:
+        """
+        Return an estimate of the memory used by the graph, in bytes, in the form:
+        {
+            'modules_and_imports': 1250000,
+            'import_details': 3400000,
+            'metadata': 250000,
+            'total': 4900000,
+        }
+
+        The metadata includes the squashed modules, external module kinds, build info, module
+        provenances and runtime imports. Objects shared between these (such as module names) are
+        only counted once.
+        """
+        raise NotImplementedError
+
+    @abc.abstractmethod
+    def enable_query_cache(self) -> None:
+        """
+        Remember the results of find_downstream_modules, find_upstream_modules,
+        find_shortest_chain, find_shortest_chains and chain_exists, so that repeated calls with the
+        same arguments return straight away. The cache is cleared whenever the graph changes.
+        """
+        raise NotImplementedError
+
+    @abc.abstractmethod
+    def disable_query_cache(self) -> None:
+        """
+        Stop remembering the results of queries, and discard any results already remembered.
+        """
+        raise NotImplementedError
+
+    @abc.abstractmethod
+    def set_build_info(self, build_info: Dict[str, Any]) -> None:
+        """
+        Record metadata about how the graph was built (see build_info).
+        """
+        raise NotImplementedError
+
+    @abc.abstractmethod
+    def build_info(self) -> Optional[Dict[str, Any]]:
+        """
+        Return metadata about how the graph was built, in the form:
+        {
+            'grimp_version': '1.2.3',
+            'package_names': ['mypackage', 'anotherpackage'],
+            'include_external_packages': False,
+            'namespace_packages': False,
+            'follow_symlinks': False,
+            'respect_gitignore': False,
+            'include_dynamic_imports': False,
+            'resolve_wildcard_imports': False,
+            'resolve_reexports': False,
+            'include_imported_objects': False,
+            'type_checking_guards': [],
+            'include_cython': False,
+            'max_file_size': None,
+            'skip_undecodable_files': False,
+            'skipped_files': [],
+            'ignored_files': [],
+            'package_directories': {},
+            'external_import_granularity': 'root',
+            'generated_file_marker': None,
+            'additional_module_extensions': [],
+            'skipped_directory_names': None,
+            'unresolved_imports': {},
+            'warnings': [],
+            'timestamp': '2021-01-19T15:04:32.101127+00:00',
+        }
+
+        The timestamp is the time (in UTC) at which the build finished. If the graph was not
+        produced by build_graph (e.g. it was assembled manually), returns None.
+        """
+        raise NotImplementedError
+
+    @abc.abstractmethod
+    def set_module_provenance(
+        self, module: str, *, package_name: str, filename: str, mtime: float
+    ) -> None:
+        """
+        Record where a module in the graph was read from (see get_module_provenance).
+        """
+        raise NotImplementedError
+
+    @abc.abstractmethod
+    def get_module_provenance(self, module: str) -> Optional[Dict[str, Any]]:
+        """
+        Return where the module was read from, in the form:
+        {
+            'package_name': 'mypackage',
+            'filename': '/path/to/mypackage/foo.py',
+            'mtime': 1611068672.1,
+        }
+
+        The package_name is the name of the package that was passed to build_graph, and the
+        mtime is the time (in seconds since the epoch) at which the file was last modified
+        when the graph was built. Returns None if the provenance of the module wasn't recorded
+        (e.g. for external modules).
+
+        Raises ModuleNotPresent if the module is not present in the graph.
+        """
+        raise NotImplementedError
+
     # Descendants
     # -----------
 
@@ -115,6 +256,17 @@ class AbstractImportGraph(abc.ABC):
         """
         raise NotImplementedError
 
+    @abc.abstractmethod
+    def find_external_modules(self, kind: Optional[str] = None) -> Set[str]:
+        """
+        Find all the modules marked as external (see add_module).
+
+        If kind is supplied (see ExternalModuleKind), only external modules of that kind are
+        returned; for example, find_external_modules(kind="third_party") excludes modules in the
+        standard library.
+        """
+        raise NotImplementedError
+
     # Direct imports
     # --------------
 
@@ -144,7 +296,7 @@ class AbstractImportGraph(abc.ABC):
     @abc.abstractmethod
     def get_import_details(
         self, *, importer: str, imported: str
-    ) -> List[Dict[str, Union[str, int]]]:
+    ) -> List[Dict[str, Union[str, int, bool, Tuple[str, ...], None]]]:
         """
         Return available metadata relating to the direct imports between two modules, in the form:
         [
@@ -153,6 +305,18 @@ class AbstractImportGraph(abc.ABC):
                 'imported': 'mypackage.imported',
                 'line_number': 5,
                 'line_contents': 'from mypackage import imported',
+                'is_dynamic': False,
+                'scope': 'module',
+                'imported_object': None,
+                'is_type_checking': False,
+                'ignored_for': (),
+                'is_cimport': False,
+                'column': 0,
+                'end_line_number': 5,
+                'platform_condition': None,
+                'is_optional': False,
+                'is_lazy': False,
+                'version_condition': None,
             },
             (additional imports here)
         ]
@@ -190,6 +354,8 @@ class AbstractImportGraph(abc.ABC):
             # Returns the modules downstream of mypackage.foo, mypackage.foo.one and
             # mypackage.foo.two.
             import_graph.find_downstream_modules('mypackage.foo', as_package=True)
+
+        If the module is not present in the graph, grimp.exceptions.ModuleNotPresent will be raised.
         """
         raise NotImplementedError
 
@@ -206,6 +372,8 @@ class AbstractImportGraph(abc.ABC):
                            treating it as a subpackage, the result will include upstream
                            modules *external* to the subpackage, and won't include modules within
                            the subpackage.
+
+        If the module is not present in the graph, grimp.exceptions.ModuleNotPresent will be raised.
         """
         raise NotImplementedError
 
@@ -268,6 +436,211 @@ class AbstractImportGraph(abc.ABC):
         """
         raise NotImplementedError
 
+    # Runtime imports
+    # ---------------
+
+    @abc.abstractmethod
+    def add_runtime_import(self, *, importer: str, imported: str) -> None:
+        """
+        Record an import that was observed at runtime.
+
+        Runtime imports are kept separately from the imports in the graph, so don't affect any
+        other queries unless they are merged into it (see merge_runtime_imports).
+        """
+        raise NotImplementedError
+
+    @abc.abstractmethod
+    def read_runtime_trace(self, filename: str) -> None:
+        """
+        Record the runtime imports in a trace file.
+
+        The file should contain a JSON object on each line, with the keys importer and imported.
+        """
+        raise NotImplementedError
+
+    @abc.abstractmethod
+    def find_runtime_imports(self, exclude_static: bool = False) -> Set[Tuple[str, str]]:
+        """
+        Return the imports observed at runtime, in the form (importer, imported).
+
+        If exclude_static is True, only return the runtime imports that aren't in the graph.
+        """
+        raise NotImplementedError
+
+    @abc.abstractmethod
+    def merge_runtime_imports(self) -> None:
+        """
+        Add the imports observed at runtime to the graph, along with any modules they involve
+        that aren't already in the graph.
+        """
+        raise NotImplementedError
+
+    # Exporting
+    # ---------
+
+    @abc.abstractmethod
+    def to_gexf(self, filename: str) -> None:
+        """
+        Write the graph to a file in GEXF format, for exploring in tools such as Gephi.
+
+        Each node has the attributes is_squashed, plus external_kind (for external modules)
+        and package_name, filename and mtime (for modules whose provenance was recorded). The
+        weight of each edge is the number of import statements it represents (or 1, if there
+        are no import details).
+        """
+        raise NotImplementedError
+
+    @abc.abstractmethod
+    def to_csv(self, filename: str) -> None:
+        """
+        Write the imports in the graph to a CSV file, with the columns importer, imported,
+        line_number and line_contents.
+
+        There is a row for each import statement, or a single row (with no line number or line
+        contents) for an import with no import details. Modules that aren't involved in any
+        imports are not included.
+        """
+        raise NotImplementedError
+
+    @classmethod
+    @abc.abstractmethod
+    def from_csv(cls, filename: str) -> "AbstractImportGraph":
+        """
+        Return a graph containing the imports in a CSV file written by to_csv.
+        """
+        raise NotImplementedError
+
+    @abc.abstractmethod
+    def to_sqlite(self, filename: str) -> None:
+        """
+        Write the graph to an SQLite database, replacing any tables of the same names.
+
+        The database has three tables: modules (with the columns name, is_squashed,
+        external_kind, package_name, filename and mtime), imports (importer and imported) and
+        import_details (importer, imported and a column for each of the fields returned by
+        get_import_details).
+        """
+        raise NotImplementedError
+
+    @abc.abstractmethod
+    def to_networkx_data(self) -> Dict[str, Any]:
+        """
+        Return the graph as node-link data, suitable for passing to networkx.node_link_graph.
+
+        Each node has the same attributes as in to_gexf. Each link has a weight (as in to_gexf)
+        and the import_details for the import (as returned by get_import_details, without the
+        importer and imported). Any build info and runtime imports are included in the graph
+        attributes.
+        """
+        raise NotImplementedError
+
+    @classmethod
+    @abc.abstractmethod
+    def from_networkx_data(cls, data: Dict[str, Any]) -> "AbstractImportGraph":
+        """
+        Return a graph from node-link data, in the format returned by to_networkx_data.
+        """
+        raise NotImplementedError
+
+    @abc.abstractmethod
+    def to_json(self, canonical: bool = False) -> str:
+        """
+        Return the graph as a JSON string, containing the data returned by to_networkx_data.
+
+        If canonical is True, the output is deterministic: keys, modules, imports and import
+        details are all sorted, whitespace is omitted, and data that varies between builds of the
+        same code (the build info, runtime imports and module provenances) is left out. Graphs
+        with the same modules and imports therefore always produce the same string.
+        """
+        raise NotImplementedError
+
+    @abc.abstractmethod
+    def write_json(self, file: TextIO, canonical: bool = False) -> None:
+        """
+        Write the JSON returned by to_json to a text file object.
+
+        The modules and imports are written one at a time, so the whole document is never held
+        in memory.
+        """
+        raise NotImplementedError
+
+    @abc.abstractmethod
+    def to_html(self, filename: str, container: Optional[str] = None) -> None:
+        """
+        Write a standalone HTML page that draws the graph at the package level, using a
+        force-directed layout.
+
+        If a container is supplied, each of its children is drawn as a single node, representing
+        it and its descendants. Otherwise, each root package is drawn as a node. The edges are
+        weighted by the number of imports between the modules each node represents.
+
+        Raises ModuleNotPresent if the container is not present in the graph, and ValueError if
+        the container is squashed.
+        """
+        raise NotImplementedError
+
+    @abc.abstractmethod
+    def save(self, filename: str) -> None:
+        """
+        Write the entire graph, including its import details and metadata, to a file that can be
+        read by load. The file contains the JSON written by write_json.
+        """
+        raise NotImplementedError
+
+    @classmethod
+    @abc.abstractmethod
+    def load(cls, filename: str) -> "AbstractImportGraph":
+        """
+        Return the graph saved to a file by save.
+
+        Raises ValueError if the file doesn't contain a saved graph.
+        """
+        raise NotImplementedError
+
+    @abc.abstractmethod
+    def export_neighbourhood(self, module: str, radius: int, format: str, filename: str) -> None:
+        """
+        Export the part of the graph within a number of imports of a module, in either
+        direction, to a file in the supplied format (see ExportFormat).
+
+        The export includes the imports between the modules in the neighbourhood, with their
+        import details, but not the build info.
+
+        Raises ModuleNotPresent if the module is not present in the graph.
+        """
+        raise NotImplementedError
+
+    # Importing from other tools
+    # --------------------------
+
+    @classmethod
+    @abc.abstractmethod
+    def from_edge_list(cls, edges: Iterable[Tuple[str, str]]) -> "AbstractImportGraph":
+        """
+        Return a graph containing the supplied imports, each in the form (importer, imported).
+        """
+        raise NotImplementedError
+
+    @classmethod
+    @abc.abstractmethod
+    def from_pydeps(cls, filename: str) -> "AbstractImportGraph":
+        """
+        Return a graph from the JSON output of pydeps (as written by 'pydeps --show-deps').
+        """
+        raise NotImplementedError
+
+    @classmethod
+    @abc.abstractmethod
+    def from_snakefood(cls, filename: str) -> "AbstractImportGraph":
+        """
+        Return a graph from the output of snakefood's sfood command, which lists the
+        dependencies between files.
+
+        Each file is converted to the name of a module by removing its extension (and any
+        trailing __init__) from its path relative to its root directory.
+        """
+        raise NotImplementedError
+
     def __repr__(self):
         """
         Display the instance in one of the following ways:
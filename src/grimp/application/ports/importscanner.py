@@ -1,8 +1,9 @@
 import abc
-from typing import Dict, Set
+from typing import Any, Dict, Iterable, List, Optional, Set
 
+from grimp import exceptions
 from grimp.application.ports.filesystem import AbstractFileSystem
-from grimp.domain.valueobjects import DirectImport, Module
+from grimp.domain.valueobjects import DirectImport, ExternalImportGranularity, Module
 
 
 class AbstractImportScanner(abc.ABC):
@@ -15,6 +16,15 @@ class AbstractImportScanner(abc.ABC):
         modules_by_package_directory: Dict[str, Set[Module]],
         file_system: AbstractFileSystem,
         include_external_packages: bool = False,
+        include_dynamic_imports: bool = False,
+        resolve_wildcard_imports: bool = False,
+        resolve_reexports: bool = False,
+        include_imported_objects: bool = False,
+        type_checking_guards: Iterable[str] = (),
+        include_cython: bool = False,
+        max_file_size: Optional[int] = None,
+        external_import_granularity: str = ExternalImportGranularity.ROOT,
+        additional_module_extensions: Iterable[str] = (),
     ) -> None:
         """
         Args:
@@ -38,10 +48,54 @@ class AbstractImportScanner(abc.ABC):
             - include_external_packages:    Whether to include imports of external modules (i.e.
                                             modules not contained in modules_by_package_directory)
                                             in the results.
+            - include_dynamic_imports:      Whether to include imports made by calling
+                                            importlib.import_module with a string literal,
+                                            e.g. importlib.import_module("foo.bar").
+            - resolve_wildcard_imports:     Whether to expand wildcard imports (e.g. 'from foo
+                                            import *') into the names listed in the imported
+                                            module's __all__, where it can be determined
+                                            statically.
+            - resolve_reexports:            Whether to resolve imports of objects that are
+                                            re-exported by the imported module (e.g. by a
+                                            facade __init__.py) to the module the object is
+                                            imported from.
+            - include_imported_objects:     Whether to record the object imported from each
+                                            module (e.g. 'foo.bar.baz' for 'from foo.bar import
+                                            baz', where baz isn't a module), as a separate import
+                                            for each object. Otherwise, each module imported by a
+                                            statement is only returned once, without an object.
+            - type_checking_guards:         The names of any constants, in addition to
+                                            TYPE_CHECKING and typing.TYPE_CHECKING, that guard
+                                            imports only needed for type checking (e.g. 'MYPY').
+            - include_cython:               Whether to scan any Cython (.pyx and .pxd) files for
+                                            each module too, including for cimports.
+            - max_file_size:                The size (in bytes) above which files will not be
+                                            scanned. Larger files cause scan_for_imports to
+                                            raise SourceFileTooLarge.
+            - external_import_granularity:  How much of the names of external modules to record
+                                            (see ExternalImportGranularity). Only relevant if
+                                            include_external_packages is True.
+            - additional_module_extensions: Extensions (such as '.pyw'), in addition to '.py',
+                                            of files that contain Python modules.
         """
         self.modules_by_package_directory = modules_by_package_directory
         self.file_system = file_system
         self.include_external_packages = include_external_packages
+        self.include_dynamic_imports = include_dynamic_imports
+        self.resolve_wildcard_imports = resolve_wildcard_imports
+        self.resolve_reexports = resolve_reexports
+        self.include_imported_objects = include_imported_objects
+        self.type_checking_guards = set(type_checking_guards)
+        self.include_cython = include_cython
+        self.max_file_size = max_file_size
+        self.external_import_granularity = external_import_granularity
+        self.additional_module_extensions = tuple(additional_module_extensions)
+        # Warnings about any problems encountered while scanning (such as imports that
+        # couldn't be resolved), for all the modules scanned so far.
+        self.warnings: List[exceptions.GrimpWarning] = []
+        # The imports of modules within the packages that couldn't be found, keyed by the
+        # importing module, in the form {"name": "mypackage.missing", "line_number": 3}.
+        self.unresolved_imports: Dict[Module, List[Dict[str, Any]]] = {}
 
         # Flatten all the modules into a set.
         self.modules: Set[Module] = set()
@@ -53,5 +107,8 @@ class AbstractImportScanner(abc.ABC):
         """
         Statically analyses the given module and returns an iterable of Modules that
         it imports.
+
+        Raises SourceFileTooLarge if any of the module's files is larger than max_file_size, or
+        SourceFileUndecodable if any of them can't be decoded.
         """
         raise NotImplementedError
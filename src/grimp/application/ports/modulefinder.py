@@ -1,11 +1,43 @@
-from typing import Iterable
+from typing import Dict, Iterable, Iterator, List, Optional
 import abc
 
+from grimp import exceptions
 from grimp.domain.valueobjects import Module
 
 from .filesystem import AbstractFileSystem
 
 
+class FoundModules:
+    """
+    The modules found in a package, along with what was learnt while finding them.
+
+    Iterating over it gives the modules.
+    """
+
+    def __init__(
+        self,
+        modules: Iterable[Module],
+        module_filenames: Optional[Dict[Module, str]] = None,
+        warnings: Optional[List[exceptions.GrimpWarning]] = None,
+        ignored_files: Optional[List[Dict[str, str]]] = None,
+    ) -> None:
+        self.modules = list(modules)
+        # The filename of each module. For modules defined by more than one file (such as Cython
+        # .pyx and .pxd files), this is the first one found.
+        self.module_filenames: Dict[Module, str] = module_filenames or {}
+        # Warnings about any problems encountered.
+        self.warnings: List[exceptions.GrimpWarning] = warnings or []
+        # The files and directories that weren't included, each in the form
+        # {"filename": ..., "reason": ...}.
+        self.ignored_files: List[Dict[str, str]] = ignored_files or []
+
+    def __iter__(self) -> Iterator[Module]:
+        return iter(self.modules)
+
+    def __len__(self) -> int:
+        return len(self.modules)
+
+
 class AbstractModuleFinder(abc.ABC):
     """
     Finds Python modules inside a package.
@@ -13,9 +45,45 @@ class AbstractModuleFinder(abc.ABC):
 
     @abc.abstractmethod
     def find_modules(
-        self, package_name: str, package_directory: str, file_system: AbstractFileSystem
+        self,
+        package_name: str,
+        package_directory: str,
+        file_system: AbstractFileSystem,
+        namespace_packages: bool = False,
+        follow_symlinks: bool = False,
+        respect_gitignore: bool = False,
+        include_cython: bool = False,
+        generated_file_marker: Optional[str] = None,
+        additional_module_extensions: Iterable[str] = (),
+        skipped_directory_names: Optional[Iterable[str]] = None,
     ) -> Iterable[Module]:
         """
         Searches the package for all importable Python modules.
+
+        Returns a FoundModules, so that the modules' filenames, any warnings and the ignored
+        files can be reported too. A plain iterable of modules is also accepted.
+
+        Args:
+            - namespace_packages: Whether to treat subdirectories without an __init__.py file as
+                                  namespace package portions (see PEP 420), rather than
+                                  ignoring them.
+            - follow_symlinks:    Whether to descend into symlinked directories. Each directory
+                                  is only searched once, even if it can be reached by more than
+                                  one path.
+            - respect_gitignore:  Whether to skip files and directories that are ignored by
+                                  .gitignore files, either within the package or in any of its
+                                  parent directories.
+            - include_cython:     Whether to include modules defined by Cython (.pyx or .pxd)
+                                  files.
+            - generated_file_marker: Text (such as '# @generated') that marks a file as
+                                  generated. Files containing it in their first few lines are
+                                  not included.
+            - additional_module_extensions: Extensions (such as '.pyw'), in addition to '.py',
+                                  of files that should be treated as Python modules.
+            - skipped_directory_names: The names of directories (such as 'build') that should
+                                  not be searched, wherever they are, unless they are packages
+                                  (i.e. contain an __init__.py file). If None, a default set of
+                                  build and virtual environment directory names is used.
+                                  Virtual environments are skipped regardless.
         """
         raise NotImplementedError
@@ -1,4 +1,5 @@
 import abc
+from typing import List
 
 from .filesystem import AbstractFileSystem
 
@@ -9,3 +10,18 @@ class AbstractPackageFinder(abc.ABC):
         self, package_name: str, file_system: AbstractFileSystem
     ) -> str:
         raise NotImplementedError
+
+    def determine_package_directories(
+        self,
+        package_name: str,
+        file_system: AbstractFileSystem,
+        namespace_packages: bool = False,
+    ) -> List[str]:
+        """
+        Return all the directories that contain portions of the package.
+
+        Most packages have a single directory, but the portions of a namespace package (see
+        PEP 420) may be spread over several. These are only supported if namespace_packages is
+        True.
+        """
+        return [self.determine_package_directory(package_name, file_system)]
@@ -0,0 +1,235 @@
+"""
+Discovery of the packages in a project, from its pyproject.toml or setup.cfg.
+"""
+import configparser
+import fnmatch
+import logging
+from typing import Any, Dict, List, Optional, Tuple
+
+from .ports.filesystem import AbstractFileSystem
+
+logger = logging.getLogger(__name__)
+
+try:
+    import tomllib as toml_parser  # type: ignore
+except ImportError:  # Python < 3.11.
+    try:
+        import tomli as toml_parser  # type: ignore
+    except ImportError:
+        toml_parser = None
+
+# Whether pyproject.toml files can be read. If not, only setup.cfg is used.
+CAN_READ_PYPROJECT_TOML = toml_parser is not None
+
+# Directories that setuptools doesn't treat as packages when they are found automatically in
+# the project directory itself (the 'flat layout').
+FLAT_LAYOUT_EXCLUDED_PACKAGES = frozenset(
+    {
+        "benchmarks",
+        "bin",
+        "build",
+        "dist",
+        "doc",
+        "docs",
+        "example",
+        "examples",
+        "scripts",
+        "test",
+        "tests",
+        "tools",
+        "venv",
+    }
+)
+
+
+class _PackageConfig:
+    """
+    The packaging configuration of a project, as far as it is needed to find its packages.
+
+    Attributes:
+        - packages: the packages listed explicitly in the configuration, or None if they
+                    should be found automatically.
+        - package_dir: mapping of package names to directories, relative to the project
+                       directory. The key '' is the directory containing the root packages.
+        - where: the directories in which to look for packages, if they are to be found
+                 automatically.
+        - exclude: patterns of package names that should not be found automatically.
+    """
+
+    def __init__(
+        self,
+        packages: Optional[List[str]] = None,
+        package_dir: Optional[Dict[str, str]] = None,
+        where: Optional[List[str]] = None,
+        exclude: Optional[List[str]] = None,
+    ) -> None:
+        self.packages = packages
+        self.package_dir = package_dir or {}
+        self.where = where
+        self.exclude = exclude or []
+
+
+def discover_packages(project_directory: str, file_system: AbstractFileSystem) -> Dict[str, str]:
+    """
+    Return the top level packages of the project, mapped to their directories.
+
+    The packages are determined from the setuptools configuration in the project's
+    pyproject.toml or setup.cfg. If neither lists the packages, they are found in the same way
+    as setuptools' automatic discovery: in the 'src' directory if there is one, otherwise
+    in the project directory itself.
+    """
+    pyproject_filename = file_system.join(project_directory, "pyproject.toml")
+    setup_cfg_filename = file_system.join(project_directory, "setup.cfg")
+
+    config: Optional[_PackageConfig] = None
+    found_config_file = False
+    if file_system.exists(pyproject_filename):
+        found_config_file = True
+        if CAN_READ_PYPROJECT_TOML:
+            config = _read_pyproject_toml(file_system.read(pyproject_filename))
+        else:
+            logger.debug("No TOML parser is installed, so ignoring {}.".format(pyproject_filename))
+    if config is None and file_system.exists(setup_cfg_filename):
+        found_config_file = True
+        config = _read_setup_cfg(file_system.read(setup_cfg_filename))
+    if not found_config_file:
+        raise ValueError(f"Could not find pyproject.toml or setup.cfg in {project_directory}.")
+    if config is None:
+        config = _PackageConfig()
+
+    if config.packages is not None:
+        return _locate_listed_packages(project_directory, config, file_system)
+    return _find_packages(project_directory, config, file_system)
+
+
+def _read_pyproject_toml(contents: str) -> Optional[_PackageConfig]:
+    data = toml_parser.loads(contents)
+    setuptools_data = data.get("tool", {}).get("setuptools", {})
+    if not setuptools_data:
+        return None
+
+    packages_data = setuptools_data.get("packages")
+    package_dir = setuptools_data.get("package-dir", {})
+    if isinstance(packages_data, list):
+        return _PackageConfig(packages=packages_data, package_dir=package_dir)
+
+    find_data: Dict[str, Any] = {}
+    if isinstance(packages_data, dict):
+        find_data = packages_data.get("find", {})
+    return _PackageConfig(
+        package_dir=package_dir,
+        where=find_data.get("where"),
+        exclude=find_data.get("exclude"),
+    )
+
+
+def _read_setup_cfg(contents: str) -> Optional[_PackageConfig]:
+    parser = configparser.ConfigParser()
+    parser.read_string(contents)
+    if not parser.has_section("options"):
+        return None
+
+    package_dir = _parse_package_dir(parser.get("options", "package_dir", fallback=""))
+    packages_value = parser.get("options", "packages", fallback="").strip()
+    if packages_value and not packages_value.startswith("find"):
+        return _PackageConfig(packages=_parse_list(packages_value), package_dir=package_dir)
+
+    where: Optional[List[str]] = None
+    exclude: Optional[List[str]] = None
+    if parser.has_section("options.packages.find"):
+        where = _parse_list(parser.get("options.packages.find", "where", fallback="")) or None
+        exclude = _parse_list(parser.get("options.packages.find", "exclude", fallback=""))
+    return _PackageConfig(package_dir=package_dir, where=where, exclude=exclude)
+
+
+def _parse_list(value: str) -> List[str]:
+    """
+    Parse a setup.cfg list, which may be separated by commas or newlines.
+    """
+    return [item.strip() for item in value.replace(",", "\n").split("\n") if item.strip()]
+
+
+def _parse_package_dir(value: str) -> Dict[str, str]:
+    """
+    Parse a setup.cfg package_dir, e.g. '=src' or 'mypackage = lib/mypackage'.
+    """
+    package_dir = {}
+    for item in _parse_list(value):
+        package_name, _, directory = item.partition("=")
+        package_dir[package_name.strip()] = directory.strip()
+    return package_dir
+
+
+def _locate_listed_packages(
+    project_directory: str, config: _PackageConfig, file_system: AbstractFileSystem
+) -> Dict[str, str]:
+    package_directories = {}
+    for package in config.packages or []:
+        # Subpackages are included in the graphs of their top level packages.
+        root_package = package.split(".")[0]
+        if root_package in package_directories:
+            continue
+        if root_package in config.package_dir:
+            directory = _join_relative(
+                file_system, project_directory, config.package_dir[root_package]
+            )
+        else:
+            directory = _join_relative(
+                file_system,
+                _join_relative(file_system, project_directory, config.package_dir.get("", "")),
+                root_package,
+            )
+        package_directories[root_package] = directory
+    return package_directories
+
+
+def _find_packages(
+    project_directory: str, config: _PackageConfig, file_system: AbstractFileSystem
+) -> Dict[str, str]:
+    if config.where is not None:
+        where = config.where
+    elif "" in config.package_dir:
+        where = [config.package_dir[""]]
+    elif "src" in _get_subdirectories(project_directory, file_system):
+        where = ["src"]
+    else:
+        where = ["."]
+
+    exclude = list(config.exclude)
+    if where == ["."] and config.where is None:
+        exclude.extend(FLAT_LAYOUT_EXCLUDED_PACKAGES)
+
+    package_directories = {}
+    for relative_directory in where:
+        directory = _join_relative(file_system, project_directory, relative_directory)
+        for package_name, package_directory in _get_packages_in_directory(directory, file_system):
+            if any(fnmatch.fnmatchcase(package_name, pattern) for pattern in exclude):
+                continue
+            package_directories[package_name] = package_directory
+    return package_directories
+
+
+def _get_packages_in_directory(
+    directory: str, file_system: AbstractFileSystem
+) -> List[Tuple[str, str]]:
+    return [
+        (dirname, file_system.join(directory, dirname))
+        for dirname in _get_subdirectories(directory, file_system)
+        if file_system.exists(file_system.join(directory, dirname, "__init__.py"))
+    ]
+
+
+def _get_subdirectories(directory: str, file_system: AbstractFileSystem) -> List[str]:
+    for _, dirnames, _ in file_system.walk(directory):
+        # Only the immediate subdirectories are of interest.
+        return sorted(dirnames)
+    return []
+
+
+def _join_relative(file_system: AbstractFileSystem, directory: str, relative_path: str) -> str:
+    components = [
+        component for component in relative_path.split("/") if component not in ("", ".")
+    ]
+    if not components:
+        return directory
+    return file_system.join(directory, *components)
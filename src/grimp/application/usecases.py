@@ -1,19 +1,52 @@
 """
 Use cases handle application logic.
 """
-from typing import List
+import logging
+import time
+import warnings
+from datetime import datetime, timezone
+from typing import Any, Callable, Dict, Iterable, List, Optional, Sequence, Union
+
+from .. import exceptions
 
 from ..application.ports.filesystem import AbstractFileSystem
 from ..application.ports.graph import AbstractImportGraph
 from ..application.ports.importscanner import AbstractImportScanner
-from ..application.ports.modulefinder import AbstractModuleFinder
+from ..application.ports.modulefinder import AbstractModuleFinder, FoundModules
 from ..application.ports.packagefinder import AbstractPackageFinder
-from ..domain.valueobjects import Module
+from ..domain.stdlib import is_stdlib_module
+from ..domain.valueobjects import ExternalImportGranularity, ExternalModuleKind, Module
+from . import projectconfig
 from .config import settings
 
+logger = logging.getLogger(__name__)
+
+# The directory in which build_graph_from_sources places its in-memory source files.
+SOURCES_DIRECTORY = "/"
+
 
 def build_graph(
-    package_name, *additional_package_names, include_external_packages: bool = False
+    package_name,
+    *additional_package_names,
+    include_external_packages: bool = False,
+    namespace_packages: bool = False,
+    follow_symlinks: bool = False,
+    respect_gitignore: bool = False,
+    include_dynamic_imports: bool = False,
+    resolve_wildcard_imports: bool = False,
+    resolve_reexports: bool = False,
+    include_imported_objects: bool = False,
+    type_checking_guards: Iterable[str] = (),
+    include_cython: bool = False,
+    max_file_size: Optional[int] = None,
+    skip_undecodable_files: bool = False,
+    progress_callback: Optional[Callable[[str, int, int], None]] = None,
+    package_directories: Optional[Dict[str, Union[str, Sequence[str]]]] = None,
+    emit_warnings: bool = False,
+    external_import_granularity: str = ExternalImportGranularity.ROOT,
+    generated_file_marker: Optional[str] = None,
+    additional_module_extensions: Iterable[str] = (),
+    skipped_directory_names: Optional[Iterable[str]] = None,
 ) -> AbstractImportGraph:
     """
     Build and return an import graph for the supplied package name(s).
@@ -22,6 +55,52 @@ def build_graph(
         - package_name: the name of the top level package for which to build the graph.
         - additional_package_names: tuple of the
         - include_external_packages: whether to include any external packages in the graph.
+        - namespace_packages: whether to include modules in subdirectories that have no
+                              __init__.py file, treating them as namespace package portions.
+                              If a package is itself a namespace package, the modules in all
+                              its portions are included.
+        - follow_symlinks: whether to descend into symlinked directories.
+        - respect_gitignore: whether to skip any files and directories ignored by .gitignore files.
+        - include_dynamic_imports: whether to include imports made by calling
+                                   importlib.import_module with a string literal, or lazily
+                                   by a module-level __getattr__.
+        - resolve_wildcard_imports: whether to expand wildcard imports using the __all__ of the
+                                    imported module.
+        - resolve_reexports: whether to resolve imports of objects re-exported by a module (e.g.
+                             an __init__.py) to the module the object is imported from.
+        - include_imported_objects: whether to record the object imported by statements such as
+                                    'from mypackage.foo import bar' (where bar isn't a module)
+                                    in the import details, with one import for each object.
+        - type_checking_guards: the names of any constants, in addition to TYPE_CHECKING and
+                                typing.TYPE_CHECKING, that guard imports only needed for type
+                                checking.
+        - include_cython: whether to include Cython (.pyx and .pxd) files, and their cimports.
+        - max_file_size: the size, in bytes, above which files are skipped rather than scanned for
+                         imports. Skipped files are listed in the build info.
+        - skip_undecodable_files: whether to skip files that can't be decoded (listing them in the
+                                  build info), rather than raising SourceFileUndecodable.
+        - progress_callback: a function to call as the build progresses. It is passed the
+                             stage ("discovering" or "scanning"), the number of items (packages
+                             or modules respectively) completed and the total number of items.
+        - package_directories: a dictionary mapping package names to their directories. Packages
+                               in this dictionary are read from these directories instead of
+                               being located on the Python path. See discover_packages. A
+                               namespace package may be mapped to a sequence of directories,
+                               each containing one of its portions.
+        - emit_warnings: whether to emit any problems encountered (which are always listed in the
+                         build info) using Python's warnings module, as GrimpWarnings.
+        - external_import_granularity: how much of the names of external modules to include in
+                                       the graph: "root" for just the top level package, or
+                                       "full" for the module named in the import statement.
+        - generated_file_marker: text, such as "# @generated", that marks a file as generated.
+                                 Files containing it in their first ten lines are left out of
+                                 the graph, and listed in the build info.
+        - additional_module_extensions: extensions, such as ".pyw", of files other than .py files
+                                        that should be treated as Python modules.
+        - skipped_directory_names: the names of directories that should never be searched for
+                                   modules, unless they contain an __init__.py file. Defaults to
+                                   common build and virtual environment directories (such as
+                                   "build" and ".venv"). Virtual environments are always skipped.
 
     Examples:
 
@@ -35,58 +114,361 @@ def build_graph(
             "mypackage", "anotherpackage", "onemore", include_external_packages=True,
         )
     """
+    return _build_graph(
+        [package_name] + list(additional_package_names),
+        file_system=settings.FILE_SYSTEM,
+        include_external_packages=include_external_packages,
+        namespace_packages=namespace_packages,
+        follow_symlinks=follow_symlinks,
+        respect_gitignore=respect_gitignore,
+        include_dynamic_imports=include_dynamic_imports,
+        resolve_wildcard_imports=resolve_wildcard_imports,
+        resolve_reexports=resolve_reexports,
+        include_imported_objects=include_imported_objects,
+        type_checking_guards=type_checking_guards,
+        include_cython=include_cython,
+        max_file_size=max_file_size,
+        skip_undecodable_files=skip_undecodable_files,
+        progress_callback=progress_callback,
+        package_directories=package_directories,
+        emit_warnings=emit_warnings,
+        external_import_granularity=external_import_granularity,
+        generated_file_marker=generated_file_marker,
+        additional_module_extensions=additional_module_extensions,
+        skipped_directory_names=skipped_directory_names,
+    )
+
+
+def build_graph_from_sources(sources: Dict[str, str], **options: Any) -> AbstractImportGraph:
+    """
+    Build and return an import graph from the source code of some modules, without reading it
+    from the file system.
+
+    Args:
+        - sources: the source code of each module, keyed by the module's name. A module is a
+                   package (i.e. an __init__.py file) if any of the other modules are within it;
+                   each top level module is always a package. Any packages that aren't included
+                   are treated as empty.
+        - options: any of the keyword arguments accepted by build_graph.
+
+    Example:
+
+        graph = build_graph_from_sources(
+            {
+                "mypackage": "",
+                "mypackage.foo": "from . import bar",
+                "mypackage.bar": "import decimal",
+            },
+            include_external_packages=True,
+        )
+    """
+    file_system = settings.IN_MEMORY_FILE_SYSTEM_CLASS()
+    module_names = set(sources)
+    for module_name in sources:
+        components = module_name.split(".")
+        module_names |= {".".join(components[:i]) for i in range(1, len(components))}
+    root_package_names = sorted(name for name in module_names if "." not in name)
+
+    for module_name in module_names:
+        components = module_name.split(".")
+        is_package = "." not in module_name or any(
+            other_name.startswith(f"{module_name}.") for other_name in module_names
+        )
+        if is_package:
+            filename = file_system.join(SOURCES_DIRECTORY, *components, "__init__.py")
+        else:
+            filename = file_system.join(
+                SOURCES_DIRECTORY, *components[:-1], f"{components[-1]}.py"
+            )
+        file_system.add_file(filename, sources.get(module_name, ""))
+
+    package_directories = {
+        package_name: file_system.join(SOURCES_DIRECTORY, package_name)
+        for package_name in root_package_names
+    }
+    return _build_graph(
+        root_package_names,
+        file_system=file_system,
+        package_directories=package_directories,
+        **options,
+    )
+
+
+def _build_graph(
+    package_names: List[str],
+    file_system: AbstractFileSystem,
+    include_external_packages: bool = False,
+    namespace_packages: bool = False,
+    follow_symlinks: bool = False,
+    respect_gitignore: bool = False,
+    include_dynamic_imports: bool = False,
+    resolve_wildcard_imports: bool = False,
+    resolve_reexports: bool = False,
+    include_imported_objects: bool = False,
+    type_checking_guards: Iterable[str] = (),
+    include_cython: bool = False,
+    max_file_size: Optional[int] = None,
+    skip_undecodable_files: bool = False,
+    progress_callback: Optional[Callable[[str, int, int], None]] = None,
+    package_directories: Optional[Dict[str, Union[str, Sequence[str]]]] = None,
+    emit_warnings: bool = False,
+    external_import_granularity: str = ExternalImportGranularity.ROOT,
+    generated_file_marker: Optional[str] = None,
+    additional_module_extensions: Iterable[str] = (),
+    skipped_directory_names: Optional[Iterable[str]] = None,
+) -> AbstractImportGraph:
     module_finder: AbstractModuleFinder = settings.MODULE_FINDER
-    file_system: AbstractFileSystem = settings.FILE_SYSTEM
     package_finder: AbstractPackageFinder = settings.PACKAGE_FINDER
 
-    package_names = [package_name] + list(additional_package_names)
     modules: List[Module] = []
     modules_by_package_directory = {}
+    # The file that defines each module found so far (or, if the module finder doesn't record
+    # filenames, the package directory it was found in). A namespace package may have portions in
+    # more than one directory.
+    location_by_module: Dict[Module, str] = {}
+    # The package name and filename of each module, and the time at which the file was modified.
+    provenance_by_module: Dict[Module, Dict[str, Any]] = {}
+    build_warnings: List[exceptions.GrimpWarning] = []
+    ignored_files: List[Dict[str, str]] = []
 
     _validate_package_names_are_strings(package_names)
+    if external_import_granularity not in (
+        ExternalImportGranularity.ROOT,
+        ExternalImportGranularity.FULL,
+    ):
+        raise ValueError(f"Unknown external import granularity {external_import_granularity}.")
+    additional_module_extensions = list(additional_module_extensions)
+    for extension in additional_module_extensions:
+        if not extension.startswith("."):
+            raise ValueError(f"Module extension {extension} should start with a dot.")
 
-    for package_name in package_names:
-        package_directory = package_finder.determine_package_directory(
-            package_name=package_name, file_system=file_system
-        )
-
-        # Build a list of all the Python modules in the package.
-        package_modules = module_finder.find_modules(
-            package_name=package_name,
-            package_directory=package_directory,
-            file_system=file_system,
-        )
-        modules.extend(package_modules)
-        modules_by_package_directory[package_directory] = set(package_modules)
+    discovery_start = time.perf_counter()
+    for package_index, package_name in enumerate(package_names, start=1):
+        if package_directories and package_name in package_directories:
+            supplied_directories = package_directories[package_name]
+            portion_directories = (
+                [supplied_directories]
+                if isinstance(supplied_directories, str)
+                else list(supplied_directories)
+            )
+        else:
+            portion_directories = package_finder.determine_package_directories(
+                package_name=package_name,
+                file_system=file_system,
+                namespace_packages=namespace_packages,
+            )
+        for package_directory in portion_directories:
+            # Build a list of all the Python modules in the package.
+            found_modules = module_finder.find_modules(
+                package_name=package_name,
+                package_directory=package_directory,
+                file_system=file_system,
+                namespace_packages=namespace_packages,
+                follow_symlinks=follow_symlinks,
+                respect_gitignore=respect_gitignore,
+                include_cython=include_cython,
+                generated_file_marker=generated_file_marker,
+                additional_module_extensions=additional_module_extensions,
+                skipped_directory_names=skipped_directory_names,
+            )
+            if not isinstance(found_modules, FoundModules):
+                # Other module finders may just return the modules.
+                found_modules = FoundModules(found_modules)
+            build_warnings.extend(found_modules.warnings)
+            ignored_files.extend(found_modules.ignored_files)
+            portion_modules = set()
+            for module in found_modules:
+                module_filename = found_modules.module_filenames.get(module)
+                location = module_filename or package_directory
+                if module in location_by_module:
+                    raise exceptions.DuplicateModuleFound(
+                        module.name, [location_by_module[module], location]
+                    )
+                location_by_module[module] = location
+                # Other module finders may not record the modules' filenames, in which case the
+                # provenance of the modules is unknown.
+                if module_filename is not None:
+                    provenance_by_module[module] = {
+                        "package_name": package_name,
+                        "filename": module_filename,
+                        "mtime": file_system.getmtime(module_filename),
+                    }
+                portion_modules.add(module)
+                modules.append(module)
+            modules_by_package_directory[package_directory] = portion_modules
+        if progress_callback:
+            progress_callback("discovering", package_index, len(package_names))
+    logger.debug(
+        f"Found {len(modules)} modules in {len(package_names)} packages in "
+        f"{time.perf_counter() - discovery_start:.3f}s."
+    )
 
     root_modules = {module.root for module in modules}
+    # The order in which the file system is walked can vary between machines. Scanning the modules
+    # in name order makes the build reproducible, so that if more than one module contains a syntax
+    # error, the same one is always reported.
+    modules.sort(key=lambda module: module.name)
 
     import_scanner: AbstractImportScanner = settings.IMPORT_SCANNER_CLASS(
         file_system=file_system,
         include_external_packages=include_external_packages,
+        include_dynamic_imports=include_dynamic_imports,
+        resolve_wildcard_imports=resolve_wildcard_imports,
+        resolve_reexports=resolve_reexports,
+        include_imported_objects=include_imported_objects,
+        type_checking_guards=type_checking_guards,
+        include_cython=include_cython,
+        max_file_size=max_file_size,
         modules_by_package_directory=modules_by_package_directory,
+        external_import_granularity=external_import_granularity,
+        additional_module_extensions=additional_module_extensions,
     )
     graph: AbstractImportGraph = settings.IMPORT_GRAPH_CLASS()
 
+    skipped_files: List[Dict[str, str]] = []
+    # The time spent parsing and resolving imports, and adding them to the graph.
+    scanning_duration = 0.0
+    building_start = time.perf_counter()
+
     # Scan each module for imports and add them to the graph.
-    for module in modules:
+    for module_index, module in enumerate(modules, start=1):
         graph.add_module(module.name)
-        for direct_import in import_scanner.scan_for_imports(module):
+        if module in provenance_by_module:
+            graph.set_module_provenance(module.name, **provenance_by_module[module])
+        scanning_start = time.perf_counter()
+        try:
+            direct_imports = import_scanner.scan_for_imports(module)
+        except (exceptions.SourceFileTooLarge, exceptions.SourceFileUndecodable) as e:
+            if isinstance(e, exceptions.SourceFileUndecodable) and not skip_undecodable_files:
+                raise
+            # Keep the module in the graph, but without any of its imports.
+            logger.warning(f"Skipping {module}: {e}")
+            skipped_files.append({"filename": e.filename, "reason": str(e)})
+            build_warnings.append(exceptions.SkippedFileWarning(f"Skipped {e.filename}: {e}"))
+            direct_imports = set()
+        scanning_duration += time.perf_counter() - scanning_start
+        for direct_import in direct_imports:
             # Before we add the import, check to see if the imported module is in fact an
-            # external module, and if so, tell the graph that it is a squashed module.
+            # external module, and if so, tell the graph that it is a squashed module, and
+            # whether it's part of the standard library.
             is_external = direct_import.imported.root not in root_modules
-            graph.add_module(direct_import.imported.name, is_squashed=is_external)
+            if is_external:
+                external_kind: Optional[str] = (
+                    ExternalModuleKind.STDLIB
+                    if is_stdlib_module(direct_import.imported.name)
+                    else ExternalModuleKind.THIRD_PARTY
+                )
+            else:
+                external_kind = None
+            graph.add_module(
+                direct_import.imported.name,
+                # With full granularity, external modules may have descendants in the graph, so
+                # can't be squashed.
+                is_squashed=(
+                    is_external
+                    and external_import_granularity == ExternalImportGranularity.ROOT
+                ),
+                external_kind=external_kind,
+            )
 
             graph.add_import(
                 importer=direct_import.importer.name,
                 imported=direct_import.imported.name,
                 line_number=direct_import.line_number,
                 line_contents=direct_import.line_contents,
+                is_dynamic=direct_import.is_dynamic,
+                scope=direct_import.scope,
+                imported_object=direct_import.imported_object,
+                is_type_checking=direct_import.is_type_checking,
+                ignored_for=direct_import.ignored_for,
+                is_cimport=direct_import.is_cimport,
+                column=direct_import.column,
+                end_line_number=direct_import.end_line_number,
+                platform_condition=direct_import.platform_condition,
+                is_optional=direct_import.is_optional,
+                is_lazy=direct_import.is_lazy,
+                version_condition=direct_import.version_condition,
             )
+        if progress_callback:
+            progress_callback("scanning", module_index, len(modules))
+    logger.debug(f"Scanned {len(modules)} modules for imports in {scanning_duration:.3f}s.")
+    logger.debug(
+        f"Added {graph.count_imports()} imports to the graph in "
+        f"{time.perf_counter() - building_start - scanning_duration:.3f}s."
+    )
+
+    build_warnings.extend(import_scanner.warnings)
+    if emit_warnings:
+        for build_warning in build_warnings:
+            # Attribute the warnings to the code that called the public function.
+            warnings.warn(build_warning, stacklevel=3)
+
+    graph.set_build_info(
+        {
+            "grimp_version": _get_grimp_version(),
+            "package_names": package_names,
+            "include_external_packages": include_external_packages,
+            "namespace_packages": namespace_packages,
+            "follow_symlinks": follow_symlinks,
+            "respect_gitignore": respect_gitignore,
+            "include_dynamic_imports": include_dynamic_imports,
+            "resolve_wildcard_imports": resolve_wildcard_imports,
+            "resolve_reexports": resolve_reexports,
+            "include_imported_objects": include_imported_objects,
+            "type_checking_guards": list(type_checking_guards),
+            "include_cython": include_cython,
+            "max_file_size": max_file_size,
+            "skip_undecodable_files": skip_undecodable_files,
+            "skipped_files": skipped_files,
+            "ignored_files": ignored_files,
+            "package_directories": {
+                package_name: directories if isinstance(directories, str) else list(directories)
+                for package_name, directories in (package_directories or {}).items()
+            },
+            "external_import_granularity": external_import_granularity,
+            "generated_file_marker": generated_file_marker,
+            "additional_module_extensions": additional_module_extensions,
+            "skipped_directory_names": (
+                None if skipped_directory_names is None else sorted(skipped_directory_names)
+            ),
+            "unresolved_imports": {
+                module.name: unresolved_imports
+                for module, unresolved_imports in sorted(
+                    import_scanner.unresolved_imports.items(), key=lambda item: item[0].name
+                )
+            },
+            "warnings": [
+                {"category": build_warning.__class__.__name__, "message": str(build_warning)}
+                for build_warning in build_warnings
+            ],
+            "timestamp": datetime.now(timezone.utc).isoformat(),
+        }
+    )
 
     return graph
 
 
+def discover_packages(project_directory: str) -> Dict[str, str]:
+    """
+    Return the top level packages of a project, mapped to their directories.
+
+    The packages are read from the project's pyproject.toml or setup.cfg. The result can be
+    passed to build_graph, so the packages don't need to be importable:
+
+        packages = discover_packages("/path/to/project")
+        graph = build_graph(*packages, package_directories=packages)
+    """
+    file_system: AbstractFileSystem = settings.FILE_SYSTEM
+    return projectconfig.discover_packages(project_directory, file_system)
+
+
+def _get_grimp_version() -> str:
+    # Imported here to avoid a circular import.
+    from grimp import __version__
+
+    return __version__.strip()
+
+
 def _validate_package_names_are_strings(package_names: List[str]) -> None:
     for name in package_names:
         if not isinstance(name, str):
@@ -0,0 +1,209 @@
+"""
+The grimp command line interface.
+
+    grimp build --output mypackage.grimp --package mypackage
+    grimp chain mypackage.foo mypackage.bar --graph mypackage.grimp
+    grimp export html mypackage.html --graph mypackage.grimp --container mypackage
+"""
+import argparse
+import sys
+from typing import List, Optional
+
+from grimp.application.config import settings
+from grimp.application.ports.graph import AbstractImportGraph
+from grimp.domain.valueobjects import ExportFormat
+from grimp.exceptions import GrimpException, ModuleNotPresent
+from grimp.main import build_graph, discover_packages
+
+
+def main(argv: Optional[List[str]] = None) -> int:
+    """
+    Run the command line interface, returning the exit code.
+    """
+    parser = _build_parser()
+    args = parser.parse_args(argv)
+    if args.command == "export" and args.module and (args.container or args.canonical):
+        parser.error("--container and --canonical can't be used with --module.")
+    try:
+        return args.handler(args)
+    except (GrimpException, ValueError, OSError) as e:
+        print(f"Error: {e}", file=sys.stderr)
+        return 2
+
+
+def _build_parser() -> argparse.ArgumentParser:
+    parser = argparse.ArgumentParser(
+        prog="grimp", description="Build and query graphs of the imports in Python packages."
+    )
+    subparsers = parser.add_subparsers(dest="command", metavar="command")
+    subparsers.required = True
+
+    build_parser = subparsers.add_parser(
+        "build", help="Build a graph and save it to a file, for use by the other commands."
+    )
+    _add_graph_building_arguments(build_parser)
+    build_parser.add_argument(
+        "--output", "-o", required=True, help="The file to save the graph to."
+    )
+    build_parser.set_defaults(handler=_build)
+
+    chain_parser = subparsers.add_parser(
+        "chain", help="Show the shortest chain of imports between two modules."
+    )
+    chain_parser.add_argument("importer", help="The module at the start of the chain.")
+    chain_parser.add_argument("imported", help="The module at the end of the chain.")
+    chain_parser.add_argument(
+        "--as-packages",
+        action="store_true",
+        help="Include chains between the modules' descendants, one for each pair of modules.",
+    )
+    _add_graph_arguments(chain_parser)
+    chain_parser.set_defaults(handler=_chain)
+
+    export_parser = subparsers.add_parser("export", help="Export a graph to a file.")
+    export_parser.add_argument("format", choices=ExportFormat.ALL, help="The format to export.")
+    export_parser.add_argument("output", help="The file to export to.")
+    export_parser.add_argument(
+        "--container", help="For html, the package whose children should be drawn."
+    )
+    export_parser.add_argument(
+        "--canonical", action="store_true", help="For json, serialize the graph deterministically."
+    )
+    export_parser.add_argument(
+        "--module", help="Only export the modules within --radius imports of this module."
+    )
+    export_parser.add_argument(
+        "--radius",
+        type=int,
+        default=1,
+        help="With --module, the number of imports to follow in either direction (default: 1).",
+    )
+    _add_graph_arguments(export_parser)
+    export_parser.set_defaults(handler=_export)
+
+    return parser
+
+
+def _add_graph_arguments(parser: argparse.ArgumentParser) -> None:
+    parser.add_argument(
+        "--graph",
+        "-g",
+        help="A graph saved by 'grimp build'. If not supplied, the graph is built instead.",
+    )
+    _add_graph_building_arguments(parser)
+
+
+def _add_graph_building_arguments(parser: argparse.ArgumentParser) -> None:
+    parser.add_argument(
+        "--package",
+        "-p",
+        action="append",
+        dest="packages",
+        help=(
+            "A package to build the graph for (may be repeated). If not supplied, the packages "
+            "are read from the pyproject.toml or setup.cfg in the project directory."
+        ),
+    )
+    parser.add_argument(
+        "--project-directory",
+        default=".",
+        help="The directory in which to look for packages (default: the current directory).",
+    )
+    parser.add_argument(
+        "--include-external-packages",
+        action="store_true",
+        help="Include external packages in the graph.",
+    )
+    parser.add_argument(
+        "--namespace-packages",
+        action="store_true",
+        help="Include modules in directories without __init__.py files.",
+    )
+    parser.add_argument(
+        "--include-dynamic-imports",
+        action="store_true",
+        help="Include imports made by importlib.import_module.",
+    )
+    parser.add_argument(
+        "--respect-gitignore",
+        action="store_true",
+        help="Leave out files and directories ignored by .gitignore files.",
+    )
+
+
+def _build(args: argparse.Namespace) -> int:
+    graph = _build_graph(args)
+    graph.save(args.output)
+    print(
+        f"Saved graph of {len(graph.modules)} modules and {graph.count_imports()} imports "
+        f"to {args.output}."
+    )
+    return 0
+
+
+def _chain(args: argparse.Namespace) -> int:
+    graph = _get_graph(args)
+    for module in (args.importer, args.imported):
+        if module not in graph.modules:
+            raise ModuleNotPresent(f'"{module}" not present in the graph.')
+    if args.as_packages:
+        chains = sorted(
+            graph.find_shortest_chains(importer=args.importer, imported=args.imported)
+        )
+    else:
+        chain = graph.find_shortest_chain(importer=args.importer, imported=args.imported)
+        chains = [chain] if chain else []
+    if not chains:
+        print(f"No chain found from {args.importer} to {args.imported}.")
+        return 1
+    for chain in chains:
+        print(" -> ".join(chain))
+    return 0
+
+
+def _export(args: argparse.Namespace) -> int:
+    graph = _get_graph(args)
+    if args.module:
+        graph.export_neighbourhood(args.module, args.radius, args.format, args.output)
+    elif args.format == ExportFormat.CSV:
+        graph.to_csv(args.output)
+    elif args.format == ExportFormat.GEXF:
+        graph.to_gexf(args.output)
+    elif args.format == ExportFormat.HTML:
+        graph.to_html(args.output, container=args.container)
+    elif args.format == ExportFormat.JSON:
+        with open(args.output, "w", encoding="utf-8") as json_file:
+            graph.write_json(json_file, canonical=args.canonical)
+    else:
+        graph.to_sqlite(args.output)
+    print(f"Exported graph to {args.output}.")
+    return 0
+
+
+def _get_graph(args: argparse.Namespace) -> AbstractImportGraph:
+    if args.graph:
+        return settings.IMPORT_GRAPH_CLASS.load(args.graph)
+    return _build_graph(args)
+
+
+def _build_graph(args: argparse.Namespace) -> AbstractImportGraph:
+    if args.packages:
+        package_names = args.packages
+        package_directories = None
+    else:
+        package_directories = discover_packages(args.project_directory)
+        if not package_directories:
+            raise ValueError(f"Could not find any packages in {args.project_directory}.")
+        package_names = sorted(package_directories)
+    return build_graph(
+        *package_names,
+        include_external_packages=args.include_external_packages,
+        namespace_packages=args.namespace_packages,
+        include_dynamic_imports=args.include_dynamic_imports,
+        respect_gitignore=args.respect_gitignore,
+        package_directories=package_directories,
+    )
+
+
+if __name__ == "__main__":
+    sys.exit(main())
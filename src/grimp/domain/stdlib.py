@@ -0,0 +1,62 @@
+"""
+Knowledge of the modules in the Python standard library.
+"""
+import functools
+import sys
+from typing import FrozenSet
+
+# The top level modules in the standard library of Python versions 3.6 to 3.10 (on any platform).
+# This is used on Python versions that don't provide sys.stdlib_module_names.
+BUNDLED_STDLIB_MODULE_NAMES: FrozenSet[str] = frozenset(
+    """
+    __future__ _abc _aix_support _ast _asyncio _bisect _blake2 _bootlocale _bootsubprocess _bz2
+    _codecs _codecs_cn _codecs_hk _codecs_iso2022 _codecs_jp _codecs_kr _codecs_tw _collections
+    _collections_abc _compat_pickle _compression _contextvars _crypt _csv _ctypes _curses
+    _curses_panel _datetime _dbm _decimal _dummy_thread _elementtree _frozen_importlib
+    _frozen_importlib_external _functools _gdbm _hashlib _heapq _imp _io _json _locale _lsprof
+    _lzma _markupbase _md5 _msi _multibytecodec _multiprocessing _opcode _operator _osx_support
+    _overlapped _peg_parser _pickle _posixshmem _posixsubprocess _py_abc _pydecimal _pyio _queue
+    _random _scproxy _sha1 _sha256 _sha3 _sha512 _signal _sitebuiltins _socket _sqlite3 _sre
+    _ssl _stat _statistics _string _strptime _struct _symtable _thread _threading_local _tkinter
+    _tracemalloc _uuid _warnings _weakref _weakrefset _winapi _zoneinfo abc aifc antigravity
+    argparse array ast asynchat asyncio asyncore atexit audioop base64 bdb binascii binhex
+    bisect builtins bz2 cProfile calendar cgi cgitb chunk cmath cmd code codecs codeop
+    collections colorsys compileall concurrent configparser contextlib contextvars copy copyreg
+    crypt csv ctypes curses dataclasses datetime dbm decimal difflib dis distutils doctest
+    dummy_threading email encodings ensurepip enum errno faulthandler fcntl filecmp fileinput
+    fnmatch formatter fractions ftplib functools gc genericpath getopt getpass gettext glob
+    graphlib grp gzip hashlib heapq hmac html http idlelib imaplib imghdr imp importlib inspect
+    io ipaddress itertools json keyword lib2to3 linecache locale logging lzma macpath
+    macurl2path mailbox mailcap marshal math mimetypes mmap modulefinder msilib msvcrt
+    multiprocessing netrc nis nntplib nt ntpath nturl2path numbers opcode operator optparse os
+    ossaudiodev parser pathlib pdb pickle pickletools pipes pkgutil platform plistlib poplib
+    posix posixpath pprint profile pstats pty pwd py_compile pyclbr pydoc pydoc_data pyexpat
+    queue quopri random re readline reprlib resource rlcompleter runpy sched secrets select
+    selectors shelve shlex shutil signal site smtpd smtplib sndhdr socket socketserver spwd
+    sqlite3 sre_compile sre_constants sre_parse ssl stat statistics string stringprep struct
+    subprocess sunau symbol symtable sys sysconfig syslog tabnanny tarfile telnetlib tempfile
+    termios test textwrap this threading time timeit tkinter token tokenize trace traceback
+    tracemalloc tty turtle turtledemo types typing unicodedata unittest urllib uu uuid venv
+    warnings wave weakref webbrowser winreg winsound wsgiref xdrlib xml xmlrpc zipapp zipfile
+    zipimport zlib zoneinfo
+    """.split()
+)
+
+
+@functools.lru_cache(maxsize=None)
+def get_stdlib_module_names() -> FrozenSet[str]:
+    """
+    Return the names of the top level modules in the standard library of the running Python.
+    """
+    # sys.stdlib_module_names was added in Python 3.10.
+    stdlib_module_names = getattr(sys, "stdlib_module_names", None)
+    if stdlib_module_names is not None:
+        return frozenset(stdlib_module_names)
+    return BUNDLED_STDLIB_MODULE_NAMES
+
+
+def is_stdlib_module(module_name: str) -> bool:
+    """
+    Return whether the supplied module (e.g. 'os.path') is part of the standard library.
+    """
+    return module_name.split(".")[0] in get_stdlib_module_names()
@@ -1,4 +1,4 @@
-from typing import Any
+from typing import Any, Optional, Tuple
 
 
 class ValueObject:
@@ -59,6 +59,56 @@ class Module(ValueObject):
         return self.name.startswith(f"{module.name}.")
 
 
+class ImportScope:
+    """
+    The scope in which an import occurs.
+    """
+
+    # At the top level of the module (including within if statements, try blocks and so on).
+    MODULE = "module"
+    # Within a function or method body.
+    FUNCTION = "function"
+    # Directly within a class body.
+    CLASS = "class"
+
+
+class ExternalModuleKind:
+    """
+    The kind of package that an external module belongs to.
+    """
+
+    # Part of the Python standard library.
+    STDLIB = "stdlib"
+    # Any other package that isn't being analysed.
+    THIRD_PARTY = "third_party"
+
+
+class ExternalImportGranularity:
+    """
+    How much of the name of an externally imported module is recorded.
+    """
+
+    # Just the top level package, e.g. 'django' for 'from django.db.models import JSONField'.
+    ROOT = "root"
+    # The module named in the import statement, e.g. 'django.db.models' (with an imported object
+    # of 'django.db.models.JSONField').
+    FULL = "full"
+
+
+class ExportFormat:
+    """
+    A file format that a graph can be exported to.
+    """
+
+    CSV = "csv"
+    GEXF = "gexf"
+    HTML = "html"
+    JSON = "json"
+    SQLITE = "sqlite"
+
+    ALL = (CSV, GEXF, HTML, JSON, SQLITE)
+
+
 class DirectImport(ValueObject):
     """
     An import between one module and another.
@@ -71,14 +121,86 @@ class DirectImport(ValueObject):
         imported: Module,
         line_number: int,
         line_contents: str,
+        is_dynamic: bool = False,
+        scope: str = ImportScope.MODULE,
+        imported_object: Optional[str] = None,
+        is_type_checking: bool = False,
+        ignored_for: Tuple[str, ...] = (),
+        is_cimport: bool = False,
+        column: Optional[int] = None,
+        end_line_number: Optional[int] = None,
+        platform_condition: Optional[str] = None,
+        is_optional: bool = False,
+        is_lazy: bool = False,
+        version_condition: Optional[str] = None,
     ) -> None:
+        """
+        Args:
+            importer:      The module that contains the import.
+            imported:      The module being imported.
+            line_number:   The line number of the import.
+            line_contents: The stripped contents of the line containing the import.
+            is_dynamic:    Whether the import happens via a call to importlib.import_module,
+                           rather than an import statement.
+            scope:         The scope in which the import occurs (see ImportScope).
+            imported_object: The full name of the object imported from the imported module,
+                           for example 'foo.bar.baz' for 'from foo.bar import baz' where baz
+                           is a function. None if the module itself is imported.
+            is_type_checking: Whether the import is guarded by a type checking constant (e.g.
+                           'if TYPE_CHECKING:'), so only happens during static analysis.
+            ignored_for:   The names given in any '# grimp: ignore[...]' comment on the line.
+            is_cimport:    Whether the import is a Cython cimport.
+            column:        The (zero-based) column at which the import statement starts.
+            end_line_number: The number of the last line of the import statement, which may
+                           span several lines.
+            platform_condition: The condition of any 'if' statement that makes the import
+                           platform-specific (e.g. 'sys.platform == "win32"'), negated if the
+                           import is in its 'else' branch.
+            is_optional:   Whether the import is optional: one whose failure is caught, by a
+                           'try' statement or 'with contextlib.suppress(...)' block that handles
+                           ImportError.
+            is_lazy:       Whether the import is made lazily, by a module-level __getattr__
+                           function (see PEP 562).
+            version_condition: The condition of any 'if' statement that makes the import
+                           depend on the Python version (e.g. 'sys.version_info >= (3, 8)'),
+                           negated if the import is in its 'else' branch.
+        """
         self.importer = importer
         self.imported = imported
         self.line_number = line_number
         self.line_contents = line_contents
+        self.is_dynamic = is_dynamic
+        self.scope = scope
+        self.imported_object = imported_object
+        self.is_type_checking = is_type_checking
+        self.ignored_for = ignored_for
+        self.is_cimport = is_cimport
+        self.column = column
+        self.end_line_number = end_line_number
+        self.platform_condition = platform_condition
+        self.is_optional = is_optional
+        self.is_lazy = is_lazy
+        self.version_condition = version_condition
 
     def __str__(self) -> str:
         return "{} -> {} (l. {})".format(self.importer, self.imported, self.line_number)
 
     def __hash__(self) -> int:
-        return hash((str(self), self.line_contents))
+        # The column and end line number only describe the position of the statement more
+        # precisely, so aren't part of the import's identity.
+        return hash(
+            (
+                str(self),
+                self.line_contents,
+                self.is_dynamic,
+                self.scope,
+                self.imported_object,
+                self.is_type_checking,
+                self.ignored_for,
+                self.is_cimport,
+                self.platform_condition,
+                self.is_optional,
+                self.is_lazy,
+                self.version_condition,
+            )
+        )
@@ -1,4 +1,4 @@
-from typing import Optional
+from typing import List, Optional
 
 
 class GrimpException(Exception):
@@ -22,6 +22,82 @@ class NamespacePackageEncountered(GrimpException):
     """
 
 
+class DuplicateModuleFound(GrimpException):
+    """
+    Indicates that the same module was found in more than one place, either in different
+    portions of a namespace package or in different packages.
+    """
+
+    def __init__(self, module: str, filenames: List[str]) -> None:
+        """
+        Args:
+            module: The name of the module.
+            filenames: The files that each define the module.
+        """
+        self.module = module
+        self.filenames = filenames
+
+    def __str__(self):
+        return f"Module {self.module} was found in more than one place: " + ", ".join(
+            self.filenames
+        )
+
+    def __eq__(self, other):
+        return (self.module, self.filenames) == (other.module, other.filenames)
+
+
+class SourceFileTooLarge(GrimpException):
+    """
+    Indicates that a file was too large to be statically analysed.
+    """
+
+    def __init__(self, filename: str, size: int, max_file_size: int) -> None:
+        """
+        Args:
+            filename: The file which was too large.
+            size: The size of the file, in bytes.
+            max_file_size: The maximum size of file that could be analysed, in bytes.
+        """
+        self.filename = filename
+        self.size = size
+        self.max_file_size = max_file_size
+
+    def __str__(self):
+        return (
+            f"{self.filename} is {self.size} bytes, which is larger than the maximum of "
+            f"{self.max_file_size} bytes."
+        )
+
+    def __eq__(self, other):
+        return (self.filename, self.size, self.max_file_size) == (
+            other.filename,
+            other.size,
+            other.max_file_size,
+        )
+
+
+class SourceFileUndecodable(GrimpException):
+    """
+    Indicates that a file could not be decoded, for example because of an invalid encoding
+    declaration.
+    """
+
+    def __init__(self, filename: str, reason: str) -> None:
+        """
+        Args:
+            filename: The file which could not be decoded.
+            reason: A description of the problem.
+        """
+        self.filename = filename
+        self.reason = reason
+
+    def __str__(self):
+        return f"Could not decode {self.filename}: {self.reason}"
+
+    def __eq__(self, other):
+        return (self.filename, self.reason) == (other.filename, other.reason)
+
+
 class SourceSyntaxError(GrimpException):
     """
     Indicates a syntax error in code that was being statically analysed.
@@ -51,3 +127,30 @@ class SourceSyntaxError(GrimpException):
             other.lineno,
             other.text,
         )
+
+
+class GrimpWarning(UserWarning):
+    """
+    Base warning for problems that Grimp works around when building a graph, rather than
+    raising an exception.
+    """
+
+
+class SkippedFileWarning(GrimpWarning):
+    """
+    Indicates that a source file was not scanned for imports (e.g. because it was too large).
+    """
+
+
+class DottedFilenameWarning(GrimpWarning):
+    """
+    Indicates that a file or directory was ignored because its name contains a dot, so it can't
+    be imported as a module.
+    """
+
+
+class UnresolvedImportWarning(GrimpWarning):
+    """
+    Indicates that an import of an internal module could not be resolved to a module in the
+    graph (e.g. a relative import beyond the top level package).
+    """
@@ -1,6 +1,40 @@
+import sys
+from typing import Any, Optional, Set
+
+
 def wrap_generator(generator, wrapper_function):
     """
     Calls each item in the generator with the function provided.
     """
     for item in generator:
         yield wrapper_function(item)
+
+
+def estimate_size(obj: Any, seen: Optional[Set[int]] = None) -> int:
+    """
+    Estimate the number of bytes used by an object, including any objects it contains.
+
+    Args:
+        - obj: the object to measure.
+        - seen: the ids of objects that have already been counted, which won't be counted
+                again. Passing the same set to several calls avoids counting shared objects
+                (such as strings) more than once.
+    """
+    if seen is None:
+        seen = set()
+    size = 0
+    objects_to_measure = [obj]
+    while objects_to_measure:
+        current = objects_to_measure.pop()
+        if id(current) in seen:
+            continue
+        seen.add(id(current))
+        size += sys.getsizeof(current)
+        if isinstance(current, dict):
+            objects_to_measure.extend(current.keys())
+            objects_to_measure.extend(current.values())
+        elif isinstance(current, (list, tuple, set, frozenset)):
+            objects_to_measure.extend(current)
+        elif hasattr(current, "__dict__") and not isinstance(current, type):
+            objects_to_measure.append(current.__dict__)
+    return size
@@ -1,18 +1,20 @@
-__all__ = ["build_graph"]
+__all__ = ["build_graph", "build_graph_from_sources", "discover_packages"]
 
-from .application.usecases import build_graph
+from .application.usecases import build_graph, build_graph_from_sources, discover_packages
 from .application.config import settings
 from .adaptors.importscanner import ImportScanner
 from .adaptors.modulefinder import ModuleFinder
-from .adaptors.filesystem import FileSystem
+from .adaptors.archivefilesystem import ArchiveFileSystem
 from .adaptors.graph import ImportGraph
+from .adaptors.memoryfilesystem import InMemoryFileSystem
 from .adaptors.packagefinder import ImportLibPackageFinder
 
 
 settings.configure(
     MODULE_FINDER=ModuleFinder(),
-    FILE_SYSTEM=FileSystem(),
+    FILE_SYSTEM=ArchiveFileSystem(),
     IMPORT_SCANNER_CLASS=ImportScanner,
     IMPORT_GRAPH_CLASS=ImportGraph,
     PACKAGE_FINDER=ImportLibPackageFinder(),
+    IN_MEMORY_FILE_SYSTEM_CLASS=InMemoryFileSystem,
 )
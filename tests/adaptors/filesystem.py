@@ -7,7 +7,10 @@ from grimp.application.ports.filesystem import AbstractFileSystem
 
 class FakeFileSystem(AbstractFileSystem):
     def __init__(
-        self, contents: str = None, content_map: Dict[str, str] = None
+        self,
+        contents: str = None,
+        content_map: Dict[str, str] = None,
+        mtime_map: Dict[str, float] = None,
     ) -> None:
         """
         Files can be declared as existing in the file system in two different ways, either
@@ -34,9 +37,13 @@ class FakeFileSystem(AbstractFileSystem):
                 {
                     '/path/to/foo/__init__.py': "from . import one",
                 }
+
+            mtime_map: A dictionary keyed with filenames, with values that are the times at which
+                       the files were last modified. Files that aren't in it have an mtime of 0.
         """
         self.contents = self._parse_contents(contents)
         self.content_map = content_map if content_map else {}
+        self.mtime_map = mtime_map if mtime_map else {}
 
     def dirname(self, filename: str) -> str:
         """
@@ -46,12 +53,14 @@ class FakeFileSystem(AbstractFileSystem):
         """
         return self.split(filename)[0]
 
-    def walk(self, directory_name):
+    def walk(self, directory_name, follow_symlinks: bool = False):
         """
         Given a directory, walk the file system recursively.
 
         For each directory in the tree rooted at directory top (including top itself),
         it yields a 3-tuple (dirpath, dirnames, filenames).
+
+        The fake file system has no symbolic links, so follow_symlinks has no effect.
         """
         try:
             directory_contents = self.contents[directory_name]
@@ -90,6 +99,10 @@ class FakeFileSystem(AbstractFileSystem):
         components = file_name.split("/")
         return ("/".join(components[:-1]), components[-1])
 
+    def realpath(self, path: str) -> str:
+        # The fake file system has no symbolic links, so all paths are already canonical.
+        return path
+
     def _parse_contents(self, raw_contents: Optional[str]):
         """
         Returns the raw contents parsed in the form:
@@ -146,6 +159,14 @@ class FakeFileSystem(AbstractFileSystem):
         dedented_lines = self._dedent(raw_lines)
         return "\n".join(dedented_lines)
 
+    def getsize(self, file_name: str) -> int:
+        return len(self.read(file_name).encode("utf-8"))
+
+    def getmtime(self, file_name: str) -> float:
+        if not self.exists(file_name):
+            raise FileNotFoundError
+        return self.mtime_map.get(file_name, 0.0)
+
     def exists(self, file_name: str) -> bool:
         # The file should exist if it's either declared in contents or in content_map.
         if file_name in self.content_map.keys():
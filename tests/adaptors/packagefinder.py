@@ -1,13 +1,28 @@
-from typing import Dict
+from typing import Dict, List, Union
 
 from grimp.application.ports.packagefinder import AbstractPackageFinder
 from grimp.application.ports.filesystem import AbstractFileSystem
 
 
 class BaseFakePackageFinder(AbstractPackageFinder):
-    directory_map: Dict[str, str] = {}
+    # Map of package names to their directories. Namespace packages with more than one portion
+    # may be mapped to a list of directories.
+    directory_map: Dict[str, Union[str, List[str]]] = {}
 
     def determine_package_directory(
         self, package_name: str, file_system: AbstractFileSystem
     ) -> str:
-        return self.directory_map[package_name]
+        directory = self.directory_map[package_name]
+        assert isinstance(directory, str)
+        return directory
+
+    def determine_package_directories(
+        self,
+        package_name: str,
+        file_system: AbstractFileSystem,
+        namespace_packages: bool = False,
+    ) -> List[str]:
+        directories = self.directory_map[package_name]
+        if isinstance(directories, str):
+            return [directories]
+        return directories
@@ -146,6 +146,18 @@ def test_get_import_details():
             "imported": "testpackage.two.alpha",
             "line_number": 5,
             "line_contents": "from .two import alpha",
+            "is_dynamic": False,
+            "scope": "function",
+            "imported_object": None,
+            "is_type_checking": False,
+            "ignored_for": (),
+            "is_cimport": False,
+            "column": 4,
+            "end_line_number": 5,
+            "platform_condition": None,
+            "is_optional": False,
+            "is_lazy": False,
+            "version_condition": None,
         }
     ] == graph.get_import_details(
         importer="testpackage.utils", imported="testpackage.two.alpha"
@@ -47,6 +47,18 @@ class TestBuildGraph:
                 "imported": "rootpackageblue.one.alpha",
                 "line_number": 1,
                 "line_contents": "from .one.alpha import BAR",
+                "is_dynamic": False,
+                "scope": "module",
+                "imported_object": None,
+                "is_type_checking": False,
+                "ignored_for": (),
+                "is_cimport": False,
+                "column": 0,
+                "end_line_number": 1,
+                "platform_condition": None,
+                "is_optional": False,
+                "is_lazy": False,
+                "version_condition": None,
             }
         ] == graph.get_import_details(
             importer="rootpackageblue.two", imported="rootpackageblue.one.alpha"
@@ -61,6 +73,18 @@ class TestBuildGraph:
                 "imported": "rootpackageblue.one.alpha",
                 "line_number": 1,
                 "line_contents": "from rootpackageblue.one import alpha",
+                "is_dynamic": False,
+                "scope": "module",
+                "imported_object": None,
+                "is_type_checking": False,
+                "ignored_for": (),
+                "is_cimport": False,
+                "column": 0,
+                "end_line_number": 1,
+                "platform_condition": None,
+                "is_optional": False,
+                "is_lazy": False,
+                "version_condition": None,
             }
         ] == graph.get_import_details(
             importer="rootpackagegreen.two", imported="rootpackageblue.one.alpha"
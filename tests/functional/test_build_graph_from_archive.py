@@ -0,0 +1,32 @@
+import sys
+import zipfile
+
+import grimp
+
+
+def test_build_graph_of_package_in_wheel(tmp_path):
+    archive_filename = str(tmp_path / "archivedpackage-1.0-py3-none-any.whl")
+    with zipfile.ZipFile(archive_filename, "w") as zip_file:
+        zip_file.writestr("archivedpackage/__init__.py", "")
+        zip_file.writestr("archivedpackage/one.py", "from . import two\n")
+        zip_file.writestr("archivedpackage/two/__init__.py", "")
+        zip_file.writestr("archivedpackage/two/three.py", "import archivedpackage.one\n")
+
+    sys.path.insert(0, archive_filename)
+    try:
+        graph = grimp.build_graph("archivedpackage")
+    finally:
+        sys.path.remove(archive_filename)
+
+    assert {
+        "archivedpackage",
+        "archivedpackage.one",
+        "archivedpackage.two",
+        "archivedpackage.two.three",
+    } == graph.modules
+    assert graph.direct_import_exists(
+        importer="archivedpackage.one", imported="archivedpackage.two"
+    )
+    assert graph.direct_import_exists(
+        importer="archivedpackage.two.three", imported="archivedpackage.one"
+    )
@@ -0,0 +1,32 @@
+import sys
+
+import grimp
+
+
+def test_build_graph_of_namespace_package_with_portions_in_different_directories(tmp_path):
+    for portion, subpackage, contents in (
+        ("one", "blue", "from nsportionspackage.green import two\n"),
+        ("two", "green", ""),
+    ):
+        subpackage_directory = tmp_path / portion / "nsportionspackage" / subpackage
+        subpackage_directory.mkdir(parents=True)
+        (subpackage_directory / "__init__.py").write_text("")
+        (subpackage_directory / f"{portion}.py").write_text(contents)
+
+    portion_paths = [str(tmp_path / "one"), str(tmp_path / "two")]
+    sys.path.extend(portion_paths)
+    try:
+        graph = grimp.build_graph("nsportionspackage", namespace_packages=True)
+    finally:
+        for portion_path in portion_paths:
+            sys.path.remove(portion_path)
+
+    assert {
+        "nsportionspackage.blue",
+        "nsportionspackage.blue.one",
+        "nsportionspackage.green",
+        "nsportionspackage.green.two",
+    } <= graph.modules
+    assert graph.direct_import_exists(
+        importer="nsportionspackage.blue.one", imported="nsportionspackage.green.two"
+    )
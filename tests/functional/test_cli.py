@@ -0,0 +1,166 @@
+import contextlib
+import io
+import json
+import pickle
+
+import pytest  # type: ignore
+
+from grimp import cli
+from grimp.adaptors.graph import ImportGraph
+
+
+def test_build_saves_graph(tmp_path):
+    filename = str(tmp_path / "testpackage.grimp")
+
+    exit_code, output = _run("build", "--package", "testpackage", "--output", filename)
+
+    assert 0 == exit_code
+    graph = ImportGraph.load(filename)
+    assert "testpackage.utils" in graph.modules
+    assert (
+        f"Saved graph of {len(graph.modules)} modules and {graph.count_imports()} imports "
+        f"to {filename}.\n"
+    ) == output
+
+
+def test_chain_prints_shortest_chain():
+    exit_code, output = _run(
+        "chain", "testpackage.utils", "testpackage.one.alpha", "--package", "testpackage"
+    )
+
+    assert 0 == exit_code
+    assert "testpackage.utils -> testpackage.two.alpha -> testpackage.one.alpha\n" == output
+
+
+def test_chain_uses_saved_graph(tmp_path):
+    filename = str(tmp_path / "graph.grimp")
+    ImportGraph.from_edge_list([("mypackage.foo", "mypackage.bar")]).save(filename)
+
+    exit_code, output = _run("chain", "mypackage.foo", "mypackage.bar", "--graph", filename)
+
+    assert 0 == exit_code
+    assert "mypackage.foo -> mypackage.bar\n" == output
+
+
+def test_chain_as_packages_prints_each_chain(tmp_path):
+    filename = str(tmp_path / "graph.grimp")
+    ImportGraph.from_edge_list(
+        [
+            ("mypackage.foo.one", "mypackage.bar.one"),
+            ("mypackage.foo.two", "mypackage.baz"),
+            ("mypackage.baz", "mypackage.bar.two"),
+            ("mypackage.foo", "mypackage"),
+            ("mypackage.bar", "mypackage"),
+        ]
+    ).save(filename)
+
+    exit_code, output = _run(
+        "chain", "mypackage.foo", "mypackage.bar", "--as-packages", "--graph", filename
+    )
+
+    assert 0 == exit_code
+    assert [
+        "mypackage.foo.one -> mypackage.bar.one",
+        "mypackage.foo.two -> mypackage.baz -> mypackage.bar.two",
+    ] == output.splitlines()
+
+
+def test_chain_exits_with_1_if_no_chain_found():
+    exit_code, output = _run(
+        "chain", "testpackage.one.alpha", "testpackage.utils", "--package", "testpackage"
+    )
+
+    assert 1 == exit_code
+    assert "No chain found from testpackage.one.alpha to testpackage.utils.\n" == output
+
+
+def test_chain_exits_with_2_if_module_not_present(tmp_path):
+    filename = str(tmp_path / "graph.grimp")
+    ImportGraph.from_edge_list([("mypackage.foo", "mypackage.bar")]).save(filename)
+    stderr = io.StringIO()
+
+    with contextlib.redirect_stderr(stderr):
+        exit_code, _ = _run("chain", "mypackage.foo", "mypackage.missing", "--graph", filename)
+
+    assert 2 == exit_code
+    assert 'Error: "mypackage.missing" not present in the graph.\n' == stderr.getvalue()
+
+
+def test_chain_exits_with_2_if_graph_file_is_not_a_saved_graph(tmp_path):
+    filename = tmp_path / "graph.grimp"
+    filename.write_bytes(pickle.dumps(ImportGraph()))
+    stderr = io.StringIO()
+
+    with contextlib.redirect_stderr(stderr):
+        exit_code, _ = _run("chain", "mypackage.foo", "mypackage.bar", "--graph", str(filename))
+
+    assert 2 == exit_code
+    assert f"Error: {filename} does not contain a saved ImportGraph.\n" == stderr.getvalue()
+
+
+def test_export_writes_file(tmp_path):
+    filename = tmp_path / "graph.json"
+
+    exit_code, output = _run(
+        "export", "json", str(filename), "--canonical", "--package", "testpackage"
+    )
+
+    assert 0 == exit_code
+    assert f"Exported graph to {filename}.\n" == output
+    data = json.loads(filename.read_text(encoding="utf-8"))
+    assert "testpackage.utils" in {node["id"] for node in data["nodes"]}
+
+
+def test_export_writes_neighbourhood_of_module(tmp_path):
+    filename = tmp_path / "graph.json"
+
+    exit_code, _ = _run(
+        "export",
+        "json",
+        str(filename),
+        "--module",
+        "testpackage.one.beta",
+        "--radius",
+        "1",
+        "--package",
+        "testpackage",
+    )
+
+    assert 0 == exit_code
+    data = json.loads(filename.read_text(encoding="utf-8"))
+    assert {"testpackage.one.alpha", "testpackage.one.beta", "testpackage.one.gamma"} == {
+        node["id"] for node in data["nodes"]
+    }
+
+
+@pytest.mark.parametrize("option", (["--canonical"], ["--container", "testpackage"]))
+def test_export_rejects_whole_graph_options_with_module(tmp_path, option):
+    stderr = io.StringIO()
+
+    with contextlib.redirect_stderr(stderr), pytest.raises(SystemExit) as exc_info:
+        _run(
+            "export",
+            "json",
+            str(tmp_path / "graph.json"),
+            "--module",
+            "testpackage.one.beta",
+            *option,
+            "--package",
+            "testpackage",
+        )
+
+    assert 2 == exc_info.value.code
+    assert "--container and --canonical can't be used with --module." in stderr.getvalue()
+
+
+def _run(*args):
+    """
+    Run the command line interface with the supplied arguments.
+
+    Returns:
+        Tuple of the exit code and the output.
+    """
+    stdout = io.StringIO()
+    with contextlib.redirect_stdout(stdout):
+        exit_code = cli.main(list(args))
+    return exit_code, stdout.getvalue()
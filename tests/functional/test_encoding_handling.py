@@ -17,5 +17,17 @@ def test_build_graph_of_non_ascii_source():
             "imported": "encodingpackage.imported",
             "line_number": 1,
             "line_contents": "from .imported import π",
+            "is_dynamic": False,
+            "scope": "module",
+            "imported_object": None,
+            "is_type_checking": False,
+            "ignored_for": (),
+            "is_cimport": False,
+            "column": 0,
+            "end_line_number": 1,
+            "platform_condition": None,
+            "is_optional": False,
+            "is_lazy": False,
+            "version_condition": None,
         },
     ] == result
@@ -0,0 +1,65 @@
+import sys
+
+import grimp
+
+
+def _create_package(directory):
+    directory.mkdir(parents=True)
+    (directory / "__init__.py").write_text("")
+    (directory / "one.py").write_text("from . import two\n")
+    (directory / "two.py").write_text("")
+
+
+def test_finds_uninstalled_package_in_src_layout(tmp_path):
+    _create_package(tmp_path / "src" / "srclayoutpackage")
+
+    project_path = str(tmp_path)
+    sys.path.insert(0, project_path)
+    try:
+        graph = grimp.build_graph("srclayoutpackage")
+    finally:
+        sys.path.remove(project_path)
+
+    assert {"srclayoutpackage", "srclayoutpackage.one", "srclayoutpackage.two"} == graph.modules
+
+
+def test_follows_editable_install_link_tree_to_source_directory(tmp_path):
+    source_directory = tmp_path / "project" / "linktreepackage"
+    _create_package(source_directory)
+    # A strict editable install links to the files that existed when it was installed, so
+    # modules added since then are only present in the source directory.
+    link_tree_directory = tmp_path / "linktree" / "linktreepackage"
+    link_tree_directory.mkdir(parents=True)
+    for filename in ("__init__.py", "one.py"):
+        (link_tree_directory / filename).symlink_to(source_directory / filename)
+
+    link_tree_path = str(tmp_path / "linktree")
+    sys.path.insert(0, link_tree_path)
+    try:
+        graph = grimp.build_graph("linktreepackage")
+    finally:
+        sys.path.remove(link_tree_path)
+
+    assert {"linktreepackage", "linktreepackage.one", "linktreepackage.two"} == graph.modules
+    assert graph.direct_import_exists(
+        importer="linktreepackage.one", imported="linktreepackage.two"
+    )
+
+
+def test_follows_editable_install_link_tree_to_differently_named_source_directory(tmp_path):
+    source_directory = tmp_path / "project" / "lib"
+    _create_package(source_directory)
+    link_tree_directory = tmp_path / "linktree" / "renamedpackage"
+    link_tree_directory.mkdir(parents=True)
+    for filename in ("__init__.py", "one.py", "two.py"):
+        (link_tree_directory / filename).symlink_to(source_directory / filename)
+
+    link_tree_path = str(tmp_path / "linktree")
+    sys.path.insert(0, link_tree_path)
+    try:
+        graph = grimp.build_graph("renamedpackage")
+    finally:
+        sys.path.remove(link_tree_path)
+
+    assert {"renamedpackage", "renamedpackage.one", "renamedpackage.two"} == graph.modules
+    assert graph.direct_import_exists(importer="renamedpackage.one", imported="renamedpackage.two")
@@ -47,6 +47,14 @@ class TestFakeFileSystem:
             "/path/to", "mypackage", "file.py"
         )
 
+    def test_realpath(self):
+        file_system = FakeFileSystem()
+        assert "/path/to/file.txt" == file_system.realpath("/path/to/file.txt")
+
+    def test_getsize(self):
+        file_system = FakeFileSystem(content_map={"/path/to/file.py": "π = 1"})
+        assert 6 == file_system.getsize("/path/to/file.py")
+
     def test_split(self):
         file_system = FakeFileSystem()
         assert ("/path/to/mypackage", "file.py") == file_system.split(
@@ -0,0 +1,166 @@
+import os
+import time
+import zipfile
+from copy import copy
+
+import pytest  # type: ignore
+
+from grimp.adaptors.archivefilesystem import ArchiveFileSystem
+
+
+class TestArchiveFileSystem:
+    def test_walk(self, tmp_path):
+        archive_filename = self._create_archive(tmp_path)
+        file_system = ArchiveFileSystem()
+        package_directory = os.path.join(archive_filename, "mypackage")
+
+        assert [
+            (package_directory, ["foo"], ["__init__.py"]),
+            (os.path.join(package_directory, "foo"), ["skipme"], ["__init__.py", "one.py"]),
+            (os.path.join(package_directory, "foo", "skipme"), [], ["two.py"]),
+        ] == list(file_system.walk(package_directory))
+
+    def test_walk_root_of_archive(self, tmp_path):
+        archive_filename = self._create_archive(tmp_path)
+        file_system = ArchiveFileSystem()
+
+        assert (archive_filename, ["mypackage", "mypackage-1.0.dist-info"], []) == next(
+            file_system.walk(archive_filename)
+        )
+
+    def test_dirnames_can_be_modified_in_place(self, tmp_path):
+        archive_filename = self._create_archive(tmp_path)
+        file_system = ArchiveFileSystem()
+        package_directory = os.path.join(archive_filename, "mypackage")
+
+        actual_tuples = []
+        for dirpath, dirnames, filenames in file_system.walk(package_directory):
+            actual_tuples.append((dirpath, copy(dirnames), filenames))
+            if "skipme" in dirnames:
+                dirnames.remove("skipme")
+
+        assert [
+            (package_directory, ["foo"], ["__init__.py"]),
+            (os.path.join(package_directory, "foo"), ["skipme"], ["__init__.py", "one.py"]),
+        ] == actual_tuples
+
+    def test_empty_if_directory_does_not_exist(self, tmp_path):
+        archive_filename = self._create_archive(tmp_path)
+        file_system = ArchiveFileSystem()
+
+        assert [] == list(file_system.walk(os.path.join(archive_filename, "nonexistent")))
+
+    def test_read(self, tmp_path):
+        archive_filename = self._create_archive(tmp_path)
+        file_system = ArchiveFileSystem()
+
+        assert "from . import foo\n" == file_system.read(
+            os.path.join(archive_filename, "mypackage", "__init__.py")
+        )
+
+    def test_read_uses_encoding_declaration(self, tmp_path):
+        archive_filename = str(tmp_path / "mydistribution.zip")
+        with zipfile.ZipFile(archive_filename, "w") as zip_file:
+            zip_file.writestr(
+                "mypackage/__init__.py", "# -*- coding: latin-1 -*-\nx = 'é'\n".encode("latin-1")
+            )
+        file_system = ArchiveFileSystem()
+
+        assert "# -*- coding: latin-1 -*-\nx = 'é'\n" == file_system.read(
+            os.path.join(archive_filename, "mypackage", "__init__.py")
+        )
+
+    def test_reads_archive_again_if_it_changes(self, tmp_path):
+        archive_filename = self._create_archive(tmp_path)
+        file_system = ArchiveFileSystem()
+        filename = os.path.join(archive_filename, "mypackage", "__init__.py")
+        file_system.read(filename)
+        original_zip_file = file_system._archive_indexes[archive_filename].zip_file
+
+        with zipfile.ZipFile(archive_filename, "w") as zip_file:
+            zip_file.writestr("mypackage/__init__.py", "from . import bar\n")
+        # Make sure the modification time changes, however coarse the file system's clock.
+        os.utime(archive_filename, ns=(0, 0))
+
+        assert "from . import bar\n" == file_system.read(filename)
+        # The file handle of the old archive is closed.
+        assert original_zip_file.fp is None
+
+    def test_read_raises_file_not_found_error(self, tmp_path):
+        archive_filename = self._create_archive(tmp_path)
+        file_system = ArchiveFileSystem()
+
+        with pytest.raises(FileNotFoundError):
+            file_system.read(os.path.join(archive_filename, "mypackage", "nonexistent.py"))
+
+    @pytest.mark.parametrize(
+        "path, expected_result",
+        (
+            (os.path.join("mypackage", "__init__.py"), True),
+            (os.path.join("mypackage", "foo", "one.py"), True),
+            (os.path.join("mypackage", "foo"), False),
+            (os.path.join("mypackage", "nonexistent.py"), False),
+        ),
+    )
+    def test_exists(self, tmp_path, path, expected_result):
+        archive_filename = self._create_archive(tmp_path)
+        file_system = ArchiveFileSystem()
+
+        assert expected_result == file_system.exists(os.path.join(archive_filename, path))
+
+    def test_getsize(self, tmp_path):
+        archive_filename = self._create_archive(tmp_path)
+        file_system = ArchiveFileSystem()
+
+        assert 18 == file_system.getsize(
+            os.path.join(archive_filename, "mypackage", "__init__.py")
+        )
+
+    def test_getmtime(self, tmp_path):
+        archive_filename = str(tmp_path / "mydistribution.whl")
+        with zipfile.ZipFile(archive_filename, "w") as zip_file:
+            zip_file.writestr(
+                zipfile.ZipInfo("mypackage/__init__.py", date_time=(2021, 1, 19, 15, 4, 32)), ""
+            )
+        file_system = ArchiveFileSystem()
+
+        assert time.mktime((2021, 1, 19, 15, 4, 32, 0, 0, -1)) == file_system.getmtime(
+            os.path.join(archive_filename, "mypackage", "__init__.py")
+        )
+        with pytest.raises(FileNotFoundError):
+            file_system.getmtime(os.path.join(archive_filename, "mypackage", "missing.py"))
+
+    def test_realpath(self, tmp_path):
+        archive_filename = self._create_archive(tmp_path)
+        file_system = ArchiveFileSystem()
+        path = os.path.join(archive_filename, "mypackage", "__init__.py")
+
+        assert (
+            os.path.join(os.path.realpath(archive_filename), "mypackage", "__init__.py")
+            == file_system.realpath(path)
+        )
+
+    def test_behaves_as_file_system_outside_archives(self, tmp_path):
+        (tmp_path / "mypackage").mkdir()
+        (tmp_path / "mypackage" / "__init__.py").write_text("import os\n")
+        package_directory = str(tmp_path / "mypackage")
+        file_system = ArchiveFileSystem()
+
+        assert [(package_directory, [], ["__init__.py"])] == list(
+            file_system.walk(package_directory)
+        )
+        assert file_system.exists(os.path.join(package_directory, "__init__.py"))
+        assert "import os\n" == file_system.read(os.path.join(package_directory, "__init__.py"))
+        assert os.path.getmtime(
+            os.path.join(package_directory, "__init__.py")
+        ) == file_system.getmtime(os.path.join(package_directory, "__init__.py"))
+
+    def _create_archive(self, tmp_path):
+        archive_filename = str(tmp_path / "mydistribution.whl")
+        with zipfile.ZipFile(archive_filename, "w") as zip_file:
+            zip_file.writestr("mypackage/__init__.py", "from . import foo\n")
+            zip_file.writestr("mypackage/foo/__init__.py", "")
+            zip_file.writestr("mypackage/foo/one.py", "from .. import foo\n")
+            zip_file.writestr("mypackage/foo/skipme/two.py", "")
+            zip_file.writestr("mypackage-1.0.dist-info/", "")
+        return archive_filename
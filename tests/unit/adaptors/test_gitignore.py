@@ -0,0 +1,78 @@
+import pytest  # type: ignore
+from grimp.adaptors.gitignore import GitIgnoreRules
+
+GITIGNORE_CONTENTS = """
+# A comment.
+build/
+*.pyc
+!keep.pyc
+/toplevel.py
+docs/**/generated_*.py
+\\#notacomment.py
+venv
+"""
+
+
+@pytest.mark.parametrize(
+    "path, is_directory, expected_result",
+    (
+        ("/path/to/build", True, True),
+        ("/path/to/build", False, False),
+        ("/path/to/foo/bar/build", True, True),
+        ("/path/to/foo.pyc", False, True),
+        ("/path/to/foo/bar.pyc", False, True),
+        ("/path/to/keep.pyc", False, False),
+        ("/path/to/toplevel.py", False, True),
+        ("/path/to/foo/toplevel.py", False, False),
+        ("/path/to/docs/generated_one.py", False, True),
+        ("/path/to/docs/foo/bar/generated_one.py", False, True),
+        ("/path/to/foo/docs/generated_one.py", False, False),
+        ("/path/to/#notacomment.py", False, True),
+        ("/path/to/venv", True, True),
+        ("/path/to/venv", False, True),
+        ("/path/to/venvs", True, False),
+        ("/path/too/venv", True, False),
+        ("/elsewhere/venv", True, False),
+    ),
+)
+def test_is_ignored(path, is_directory, expected_result):
+    rules = GitIgnoreRules()
+    rules.add_rules("/path/to", GITIGNORE_CONTENTS)
+
+    assert expected_result == rules.is_ignored(path, is_directory=is_directory)
+
+
+@pytest.mark.parametrize(
+    "inner_contents, expected_result",
+    (
+        ("", True),
+        ("!*.pyc", False),
+    ),
+)
+def test_later_rules_take_precedence(inner_contents, expected_result):
+    rules = GitIgnoreRules()
+    rules.add_rules("/path/to", "*.pyc")
+    rules.add_rules("/path/to/foo", inner_contents)
+
+    assert expected_result == rules.is_ignored("/path/to/foo/bar.pyc", is_directory=False)
+
+
+@pytest.mark.parametrize(
+    "pattern, path, expected_result",
+    (
+        ("foo?.py", "/path/to/foo1.py", True),
+        ("foo?.py", "/path/to/foo.py", False),
+        ("foo[0-9].py", "/path/to/foo1.py", True),
+        ("foo[!0-9].py", "/path/to/foo1.py", False),
+        ("foo[!0-9].py", "/path/to/fooa.py", True),
+        ("**/foo.py", "/path/to/bar/foo.py", True),
+        ("bar/**", "/path/to/bar/baz/foo.py", True),
+        ("bar/**", "/path/to/baz/bar/foo.py", False),
+        ("bar/*.py", "/path/to/bar/baz/foo.py", False),
+    ),
+)
+def test_wildcards(pattern, path, expected_result):
+    rules = GitIgnoreRules()
+    rules.add_rules("/path/to", pattern)
+
+    assert expected_result == rules.is_ignored(path, is_directory=False)
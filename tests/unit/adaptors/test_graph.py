@@ -1,6 +1,14 @@
+import io
+import json
+import pickle
 import re
+import sqlite3
+from unittest import mock
+from xml.etree import ElementTree
 
+import networkx  # type: ignore
 import pytest  # type: ignore
+from grimp.adaptors import graph as graph_module
 from grimp.adaptors.graph import ImportGraph
 from grimp.exceptions import ModuleNotPresent
 
@@ -275,6 +283,46 @@ def test_find_upstream_modules(module, as_package, expected_result):
     assert expected_result == graph.find_upstream_modules(module, as_package=as_package)
 
 
+def test_find_downstream_and_upstream_modules_within_import_cycle():
+    graph = ImportGraph.from_edge_list(
+        [
+            ("mypackage.a", "mypackage.b"),
+            ("mypackage.b", "mypackage.c"),
+            ("mypackage.c", "mypackage.a"),
+            ("mypackage.c", "mypackage.d"),
+            ("mypackage.e", "mypackage.a"),
+        ]
+    )
+
+    assert {"mypackage.a", "mypackage.c", "mypackage.e"} == graph.find_downstream_modules(
+        "mypackage.b"
+    )
+    assert {"mypackage.a", "mypackage.c", "mypackage.d"} == graph.find_upstream_modules(
+        "mypackage.b"
+    )
+
+
+def test_find_downstream_and_upstream_modules_reflect_changes_to_graph():
+    graph = ImportGraph.from_edge_list([("mypackage.a", "mypackage.b")])
+    assert {"mypackage.a"} == graph.find_downstream_modules("mypackage.b")
+
+    graph.add_import(importer="mypackage.c", imported="mypackage.a")
+    assert {"mypackage.a", "mypackage.c"} == graph.find_downstream_modules("mypackage.b")
+    assert {"mypackage.a", "mypackage.b"} == graph.find_upstream_modules("mypackage.c")
+
+    graph.remove_import(importer="mypackage.a", imported="mypackage.b")
+    assert set() == graph.find_downstream_modules("mypackage.b")
+    assert {"mypackage.a"} == graph.find_upstream_modules("mypackage.c")
+
+
+@pytest.mark.parametrize("method_name", ("find_downstream_modules", "find_upstream_modules"))
+def test_find_downstream_and_upstream_modules_raise_module_not_present(method_name):
+    graph = ImportGraph.from_edge_list([("mypackage.a", "mypackage.b")])
+
+    with pytest.raises(ModuleNotPresent):
+        getattr(graph, method_name)("mypackage.missing")
+
+
 @pytest.mark.parametrize(
     "module, expected_result",
     (("foo", {"foo.a", "foo.b", "foo.c"}), ("foo.a", {"foo.a.one"}), ("foo.c", set())),
@@ -332,6 +380,33 @@ def test_find_descendants_raises_exception_for_squashed_module():
         graph.find_descendants(module)
 
 
+def test_find_descendants_reflects_changes_to_graph():
+    graph = ImportGraph()
+    graph.add_module("foo")
+    graph.add_module("foo.a")
+    assert {"foo.a"} == graph.find_descendants("foo")
+
+    graph.add_module("foo.b")
+    graph.add_module("foo.b.c")
+    assert {"foo.a", "foo.b", "foo.b.c"} == graph.find_descendants("foo")
+
+    graph.squash_module("foo.b")
+    assert {"foo.a", "foo.b"} == graph.find_descendants("foo")
+
+    graph.remove_module("foo.a")
+    assert {"foo.b"} == graph.find_descendants("foo")
+
+
+def test_find_descendants_result_can_be_modified_safely():
+    graph = ImportGraph()
+    graph.add_module("foo")
+    graph.add_module("foo.a")
+
+    graph.find_descendants("foo").add("foo.b")
+
+    assert {"foo.a"} == graph.find_descendants("foo")
+
+
 def test_find_shortest_chain_when_exists():
     graph = ImportGraph()
     a, b, c = "foo", "bar", "baz"
@@ -523,6 +598,31 @@ class TestFindShortestChains:
             ),
         }
 
+    def test_does_not_modify_the_graph(self):
+        graph = ImportGraph()
+        graph.add_module("green")
+        graph.add_module("blue")
+        graph.add_import(importer="green.foo", imported="green.bar")
+        graph.add_import(importer="green.bar", imported="blue.foo")
+        graph.add_import(importer="blue.foo", imported="blue.bar")
+        graph.add_import(importer="green.foo", imported="brown")
+        graph.add_import(importer="brown", imported="blue.bar")
+        graph_before = graph.to_json(canonical=True)
+
+        def fail(*args, **kwargs):
+            raise AssertionError("The graph was modified.")
+
+        with mock.patch.object(networkx.DiGraph, "add_edge", fail), mock.patch.object(
+            networkx.DiGraph, "remove_edge", fail
+        ):
+            result = graph.find_shortest_chains(importer="green", imported="blue")
+
+        assert result == {
+            ("green.bar", "blue.foo"),
+            ("green.foo", "brown", "blue.bar"),
+        }
+        assert graph.to_json(canonical=True) == graph_before
+
 
 @pytest.mark.parametrize(
     "importer, imported, as_packages, expected_result",
@@ -686,6 +786,115 @@ def test_chain_exists(importer, imported, as_packages, expected_result):
         assert expected_result == graph.chain_exists(**kwargs)
 
 
+def test_chain_exists_within_import_cycle():
+    graph = ImportGraph.from_edge_list(
+        [
+            ("mypackage.a", "mypackage.b"),
+            ("mypackage.b", "mypackage.c"),
+            ("mypackage.c", "mypackage.a"),
+        ]
+    )
+
+    assert graph.chain_exists(importer="mypackage.c", imported="mypackage.b")
+    assert graph.chain_exists(importer="mypackage.b", imported="mypackage.a")
+
+
+def test_chain_exists_reflects_changes_to_graph():
+    graph = ImportGraph.from_edge_list([("mypackage.a", "mypackage.b")])
+    graph.add_module("mypackage.c")
+    assert not graph.chain_exists(importer="mypackage.a", imported="mypackage.c")
+
+    graph.add_import(importer="mypackage.b", imported="mypackage.c")
+    assert graph.chain_exists(importer="mypackage.a", imported="mypackage.c")
+
+    graph.remove_import(importer="mypackage.a", imported="mypackage.b")
+    assert not graph.chain_exists(importer="mypackage.a", imported="mypackage.c")
+
+    graph.add_import(importer="mypackage.a", imported="mypackage.d")
+    graph.add_import(importer="mypackage.d", imported="mypackage.c")
+    assert graph.chain_exists(importer="mypackage.a", imported="mypackage.c")
+
+    graph.remove_module("mypackage.d")
+    assert not graph.chain_exists(importer="mypackage.a", imported="mypackage.c")
+
+
+class TestQueryCache:
+    def _build_graph(self):
+        return ImportGraph.from_edge_list(
+            [("mypackage.a", "mypackage.b"), ("mypackage.b", "mypackage.c")]
+        )
+
+    def test_repeated_query_is_answered_from_cache(self):
+        graph = self._build_graph()
+        graph.enable_query_cache()
+        assert graph.find_downstream_modules("mypackage.c") == {"mypackage.a", "mypackage.b"}
+
+        with mock.patch.object(
+            ImportGraph, "_find_connected_modules", side_effect=AssertionError
+        ):
+            result = graph.find_downstream_modules("mypackage.c")
+
+        assert result == {"mypackage.a", "mypackage.b"}
+
+    def test_results_cannot_be_changed_by_caller(self):
+        graph = self._build_graph()
+        graph.enable_query_cache()
+
+        graph.find_downstream_modules("mypackage.c").add("mypackage.z")
+
+        assert graph.find_downstream_modules("mypackage.c") == {"mypackage.a", "mypackage.b"}
+
+    def test_cache_is_cleared_when_graph_changes(self):
+        graph = self._build_graph()
+        graph.enable_query_cache()
+        assert graph.chain_exists(importer="mypackage.a", imported="mypackage.c")
+        assert graph.find_shortest_chain(importer="mypackage.a", imported="mypackage.c") == (
+            "mypackage.a",
+            "mypackage.b",
+            "mypackage.c",
+        )
+
+        graph.remove_import(importer="mypackage.b", imported="mypackage.c")
+
+        assert not graph.chain_exists(importer="mypackage.a", imported="mypackage.c")
+        assert graph.find_shortest_chain(importer="mypackage.a", imported="mypackage.c") is None
+
+    def test_disabled_by_default(self):
+        graph = self._build_graph()
+        graph.find_downstream_modules("mypackage.c")
+
+        with pytest.raises(AssertionError):
+            with mock.patch.object(
+                ImportGraph, "_find_connected_modules", side_effect=AssertionError
+            ):
+                graph.find_downstream_modules("mypackage.c")
+
+    def test_disable_query_cache(self):
+        graph = self._build_graph()
+        graph.enable_query_cache()
+        graph.find_downstream_modules("mypackage.c")
+
+        graph.disable_query_cache()
+
+        with pytest.raises(AssertionError):
+            with mock.patch.object(
+                ImportGraph, "_find_connected_modules", side_effect=AssertionError
+            ):
+                graph.find_downstream_modules("mypackage.c")
+
+
+def test_logs_duration_of_queries():
+    graph = ImportGraph.from_edge_list([("mypackage.a", "mypackage.b")])
+
+    with mock.patch.object(graph_module, "logger") as logger:
+        graph.chain_exists(importer="mypackage.a", imported="mypackage.b")
+
+    [message] = [call[0][0] for call in logger.debug.call_args_list]
+    assert message.startswith(
+        "chain_exists(importer='mypackage.a', imported='mypackage.b') took "
+    )
+
+
 def test_add_module():
     graph = ImportGraph()
     module = "foo"
@@ -802,12 +1011,54 @@ class TestGetImportDetails:
                 imported="mypackage.bar",
                 line_number=1,
                 line_contents="from . import bar",
+                is_dynamic=False,
+                scope="module",
+                imported_object=None,
+                is_type_checking=False,
+                ignored_for=(),
+                is_cimport=False,
+                column=0,
+                end_line_number=1,
+                platform_condition=None,
+                is_optional=False,
+                is_lazy=False,
+                version_condition=None,
             ),
             dict(
                 importer="mypackage.foo",
                 imported="mypackage.bar",
                 line_number=10,
                 line_contents="from .bar import a_function",
+                is_dynamic=False,
+                scope="module",
+                imported_object=None,
+                is_type_checking=False,
+                ignored_for=(),
+                is_cimport=False,
+                column=4,
+                end_line_number=12,
+                platform_condition=None,
+                is_optional=False,
+                is_lazy=False,
+                version_condition=None,
+            ),
+            dict(
+                importer="mypackage.foo",
+                imported="mypackage.bar",
+                line_number=20,
+                line_contents='importlib.import_module("mypackage.bar")',
+                is_dynamic=True,
+                scope="module",
+                imported_object=None,
+                is_type_checking=False,
+                ignored_for=(),
+                is_cimport=False,
+                column=0,
+                end_line_number=20,
+                platform_condition=None,
+                is_optional=False,
+                is_lazy=False,
+                version_condition=None,
             ),
         ]
         for import_info in imports_info:
@@ -839,6 +1090,18 @@ class TestGetImportDetails:
                 imported="mypackage.bar",
                 line_number=1,
                 line_contents="from . import bar",
+                is_dynamic=False,
+                scope="module",
+                imported_object=None,
+                is_type_checking=False,
+                ignored_for=(),
+                is_cimport=False,
+                column=None,
+                end_line_number=None,
+                platform_condition=None,
+                is_optional=False,
+                is_lazy=False,
+                version_condition=None,
             )
         ]
         graph.add_import(**imports_info[0])
@@ -856,6 +1119,152 @@ class TestGetImportDetails:
         )
 
 
+class TestMemoryUsage:
+    def test_total_is_sum_of_parts(self):
+        graph = ImportGraph.from_edge_list([("mypackage.foo", "mypackage.bar")])
+
+        result = graph.memory_usage()
+
+        assert {"modules_and_imports", "import_details", "metadata", "total"} == set(result)
+        assert result["total"] == (
+            result["modules_and_imports"] + result["import_details"] + result["metadata"]
+        )
+
+    def test_grows_with_graph(self):
+        graph = ImportGraph()
+        empty_usage = graph.memory_usage()
+
+        for index in range(100):
+            graph.add_import(
+                importer=f"mypackage.module_{index}",
+                imported="mypackage.base",
+                line_number=1,
+                line_contents="from mypackage import base",
+            )
+            graph.set_module_provenance(
+                f"mypackage.module_{index}",
+                package_name="mypackage",
+                filename=f"/path/to/mypackage/module_{index}.py",
+                mtime=1.5,
+            )
+
+        usage = graph.memory_usage()
+        for part in ("modules_and_imports", "import_details", "metadata", "total"):
+            assert usage[part] > empty_usage[part]
+
+
+class TestBuildInfo:
+    def test_returns_none_if_not_set(self):
+        graph = ImportGraph()
+
+        assert graph.build_info() is None
+
+    def test_returns_build_info_once_set(self):
+        graph = ImportGraph()
+        build_info = {
+            "grimp_version": "1.2.3",
+            "package_names": ["mypackage"],
+            "include_external_packages": False,
+            "timestamp": "2021-01-19T15:04:32.101127+00:00",
+        }
+
+        graph.set_build_info(build_info)
+
+        assert build_info == graph.build_info()
+
+    def test_returned_build_info_cannot_alter_graph(self):
+        graph = ImportGraph()
+        graph.set_build_info({"package_names": ["mypackage"]})
+
+        graph.build_info()["package_names"].append("anotherpackage")
+
+        assert {"package_names": ["mypackage"]} == graph.build_info()
+
+
+class TestModuleProvenance:
+    def test_returns_none_if_not_set(self):
+        graph = ImportGraph()
+        graph.add_module("mypackage")
+
+        assert graph.get_module_provenance("mypackage") is None
+
+    def test_returns_provenance_once_set(self):
+        graph = ImportGraph()
+        graph.add_module("mypackage.foo")
+
+        graph.set_module_provenance(
+            "mypackage.foo",
+            package_name="mypackage",
+            filename="/path/to/mypackage/foo.py",
+            mtime=1611068672.1,
+        )
+
+        assert {
+            "package_name": "mypackage",
+            "filename": "/path/to/mypackage/foo.py",
+            "mtime": 1611068672.1,
+        } == graph.get_module_provenance("mypackage.foo")
+
+    def test_provenance_is_removed_with_module(self):
+        graph = ImportGraph()
+        graph.add_module("mypackage.foo")
+        graph.set_module_provenance(
+            "mypackage.foo", package_name="mypackage", filename="/path/to/foo.py", mtime=1.0
+        )
+
+        graph.remove_module("mypackage.foo")
+        graph.add_module("mypackage.foo")
+
+        assert graph.get_module_provenance("mypackage.foo") is None
+
+    def test_get_raises_module_not_present(self):
+        graph = ImportGraph()
+
+        with pytest.raises(ModuleNotPresent, match='"mypackage" not present in the graph.'):
+            graph.get_module_provenance("mypackage")
+
+    def test_set_raises_module_not_present(self):
+        graph = ImportGraph()
+
+        with pytest.raises(ModuleNotPresent, match='"mypackage" not present in the graph.'):
+            graph.set_module_provenance(
+                "mypackage", package_name="mypackage", filename="/path/to/foo.py", mtime=1.0
+            )
+
+
+class TestFindExternalModules:
+    @pytest.mark.parametrize(
+        "kind, expected_result",
+        (
+            (None, {"decimal", "os", "django"}),
+            ("stdlib", {"decimal", "os"}),
+            ("third_party", {"django"}),
+        ),
+    )
+    def test_returns_modules_added_with_external_kind(self, kind, expected_result):
+        graph = ImportGraph()
+        graph.add_module("mypackage")
+        graph.add_module("decimal", is_squashed=True, external_kind="stdlib")
+        graph.add_module("os", is_squashed=True, external_kind="stdlib")
+        graph.add_module("django", is_squashed=True, external_kind="third_party")
+
+        assert expected_result == graph.find_external_modules(kind=kind)
+
+    def test_excludes_removed_modules(self):
+        graph = ImportGraph()
+        graph.add_module("django", is_squashed=True, external_kind="third_party")
+
+        graph.remove_module("django")
+
+        assert set() == graph.find_external_modules()
+
+    def test_raises_value_error_for_unknown_kind(self):
+        graph = ImportGraph()
+
+        with pytest.raises(ValueError, match="Unknown external module kind builtin."):
+            graph.add_module("sys", external_kind="builtin")
+
+
 class TestIsModuleSquashed:
     def test_returns_true_for_module_added_with_is_squashed(self):
         graph = ImportGraph()
@@ -991,6 +1400,18 @@ class TestSquashModule:
             imported="bar.blue",
             line_number=1,
             line_contents="from . import bar",
+            is_dynamic=False,
+            scope="module",
+            imported_object=None,
+            is_type_checking=False,
+            ignored_for=(),
+            is_cimport=False,
+            column=None,
+            end_line_number=None,
+            platform_condition=None,
+            is_optional=False,
+            is_lazy=False,
+            version_condition=None,
         )
         graph.add_import(**import_details)
 
@@ -1013,6 +1434,18 @@ class TestSquashModule:
             imported="foo",
             line_number=1,
             line_contents="from . import foo",
+            is_dynamic=False,
+            scope="module",
+            imported_object=None,
+            is_type_checking=False,
+            ignored_for=(),
+            is_cimport=False,
+            column=None,
+            end_line_number=None,
+            platform_condition=None,
+            is_optional=False,
+            is_lazy=False,
+            version_condition=None,
         )
         graph.add_import(**import_details)
 
@@ -1189,3 +1622,933 @@ class TestFindAllSimpleChains:
             graph.add_import(
                 importer=modules[index], imported=modules[index + 1],
             )
+
+
+class TestToGexf:
+    GEXF_NAMESPACE = "{http://www.gexf.net/1.2draft}"
+
+    def test_writes_modules_and_imports(self, tmp_path):
+        graph = ImportGraph()
+        graph.add_import(importer="mypackage.foo", imported="mypackage.bar")
+        graph.add_module("mypackage.baz")
+        filename = str(tmp_path / "graph.gexf")
+
+        graph.to_gexf(filename)
+
+        nodes, edges = self._read_gexf(filename)
+        assert {"mypackage.foo", "mypackage.bar", "mypackage.baz"} == set(nodes)
+        assert {("mypackage.foo", "mypackage.bar")} == set(edges)
+
+    def test_weights_edges_by_number_of_import_statements(self, tmp_path):
+        graph = ImportGraph()
+        for line_number in (1, 5):
+            graph.add_import(
+                importer="mypackage.foo",
+                imported="mypackage.bar",
+                line_number=line_number,
+                line_contents="import mypackage.bar",
+            )
+        graph.add_import(importer="mypackage.foo", imported="mypackage.baz")
+        filename = str(tmp_path / "graph.gexf")
+
+        graph.to_gexf(filename)
+
+        _, edges = self._read_gexf(filename)
+        assert 2 == float(edges[("mypackage.foo", "mypackage.bar")].get("weight"))
+        assert 1 == float(edges[("mypackage.foo", "mypackage.baz")].get("weight"))
+
+    def test_includes_module_metadata(self, tmp_path):
+        graph = ImportGraph()
+        graph.add_module("mypackage.foo")
+        graph.set_module_provenance(
+            "mypackage.foo",
+            package_name="mypackage",
+            filename="/path/to/mypackage/foo.py",
+            mtime=1611068672.5,
+        )
+        graph.add_module("mypackage.bar", is_squashed=True)
+        graph.add_module("django", is_squashed=True, external_kind="third_party")
+        filename = str(tmp_path / "graph.gexf")
+
+        graph.to_gexf(filename)
+
+        nodes, _ = self._read_gexf(filename)
+        assert {
+            "is_squashed": "false",
+            "package_name": "mypackage",
+            "filename": "/path/to/mypackage/foo.py",
+            "mtime": "1611068672.5",
+        } == nodes["mypackage.foo"]
+        assert {"is_squashed": "true"} == nodes["mypackage.bar"]
+        assert {"is_squashed": "true", "external_kind": "third_party"} == nodes["django"]
+
+    def _read_gexf(self, filename):
+        """
+        Return the nodes (keyed by label, with their attribute values) and edges (keyed by
+        source and target labels) in the supplied GEXF file.
+        """
+        namespace = self.GEXF_NAMESPACE
+        root = ElementTree.parse(filename).getroot()
+        attribute_titles = {
+            attribute.get("id"): attribute.get("title")
+            for attribute in root.iter(f"{namespace}attribute")
+        }
+        labels = {}
+        nodes = {}
+        for node in root.iter(f"{namespace}node"):
+            labels[node.get("id")] = node.get("label")
+            nodes[node.get("label")] = {
+                attribute_titles[value.get("for")]: value.get("value")
+                for value in node.iter(f"{namespace}attvalue")
+            }
+        edges = {
+            (labels[edge.get("source")], labels[edge.get("target")]): edge
+            for edge in root.iter(f"{namespace}edge")
+        }
+        return nodes, edges
+
+
+class TestToCsv:
+    def test_writes_a_row_for_each_import_statement(self, tmp_path):
+        graph = ImportGraph()
+        graph.add_import(
+            importer="mypackage.foo",
+            imported="mypackage.bar",
+            line_number=5,
+            line_contents="from mypackage import bar",
+        )
+        graph.add_import(
+            importer="mypackage.foo",
+            imported="mypackage.bar",
+            line_number=1,
+            line_contents="import mypackage.bar",
+        )
+        graph.add_import(importer="mypackage.bar", imported="mypackage.baz")
+        graph.add_module("mypackage.unimported")
+        filename = tmp_path / "graph.csv"
+
+        graph.to_csv(str(filename))
+
+        assert [
+            "importer,imported,line_number,line_contents",
+            "mypackage.bar,mypackage.baz,,",
+            "mypackage.foo,mypackage.bar,1,import mypackage.bar",
+            "mypackage.foo,mypackage.bar,5,from mypackage import bar",
+        ] == filename.read_text(encoding="utf-8").splitlines()
+
+    def test_excludes_details_of_removed_imports(self, tmp_path):
+        graph = ImportGraph()
+        graph.add_import(
+            importer="mypackage.foo",
+            imported="mypackage.bar",
+            line_number=1,
+            line_contents="import mypackage.bar",
+        )
+        graph.remove_import(importer="mypackage.foo", imported="mypackage.bar")
+        filename = tmp_path / "graph.csv"
+
+        graph.to_csv(str(filename))
+
+        assert [
+            "importer,imported,line_number,line_contents"
+        ] == filename.read_text(encoding="utf-8").splitlines()
+
+
+class TestFromCsv:
+    def test_round_trips_imports(self, tmp_path):
+        graph = ImportGraph()
+        graph.add_import(
+            importer="mypackage.foo",
+            imported="mypackage.bar",
+            line_number=1,
+            line_contents='import mypackage.bar  # A "quoted", commented import.',
+        )
+        graph.add_import(importer="mypackage.bar", imported="mypackage.baz")
+        filename = str(tmp_path / "graph.csv")
+        graph.to_csv(filename)
+
+        result = ImportGraph.from_csv(filename)
+
+        assert {"mypackage.foo", "mypackage.bar", "mypackage.baz"} == result.modules
+        assert 2 == result.count_imports()
+        assert [
+            {
+                "line_number": 1,
+                "line_contents": 'import mypackage.bar  # A "quoted", commented import.',
+            }
+        ] == [
+            {"line_number": d["line_number"], "line_contents": d["line_contents"]}
+            for d in result.get_import_details(importer="mypackage.foo", imported="mypackage.bar")
+        ]
+        assert [] == result.get_import_details(
+            importer="mypackage.bar", imported="mypackage.baz"
+        )
+
+
+class TestSaveAndLoad:
+    def test_round_trips_graph(self, tmp_path):
+        graph = ImportGraph()
+        graph.add_import(
+            importer="mypackage.foo",
+            imported="mypackage.bar",
+            line_number=1,
+            line_contents="import mypackage.bar",
+            is_type_checking=True,
+        )
+        graph.add_module("mypackage.baz", is_squashed=True)
+        graph.add_module("django", is_squashed=True, external_kind="third_party")
+        graph.add_import(importer="mypackage.bar", imported="django")
+        graph.set_module_provenance(
+            "mypackage.foo", package_name="mypackage", filename="/path/to/foo.py", mtime=1.5
+        )
+        graph.set_build_info({"package_names": ["mypackage"]})
+        graph.add_runtime_import(importer="mypackage.foo", imported="mypackage.baz")
+        filename = str(tmp_path / "graph.grimp")
+        graph.save(filename)
+
+        result = ImportGraph.load(filename)
+
+        assert graph.modules == result.modules
+        assert 2 == result.count_imports()
+        assert graph.get_import_details(
+            importer="mypackage.foo", imported="mypackage.bar"
+        ) == result.get_import_details(importer="mypackage.foo", imported="mypackage.bar")
+        assert result.is_module_squashed("mypackage.baz")
+        assert {"django"} == result.find_external_modules(kind="third_party")
+        assert graph.get_module_provenance("mypackage.foo") == result.get_module_provenance(
+            "mypackage.foo"
+        )
+        assert {"package_names": ["mypackage"]} == result.build_info()
+        assert {("mypackage.foo", "mypackage.baz")} == result.find_runtime_imports()
+
+    def test_saves_graph_as_json(self, tmp_path):
+        graph = ImportGraph.from_edge_list([("mypackage.foo", "mypackage.bar")])
+        filename = tmp_path / "graph.grimp"
+
+        graph.save(str(filename))
+
+        assert graph.to_json() == filename.read_text(encoding="utf-8")
+
+    def test_loaded_graph_can_be_modified(self, tmp_path):
+        graph = ImportGraph()
+        graph.add_import(importer="mypackage.foo", imported="mypackage.bar")
+        filename = str(tmp_path / "graph.grimp")
+        graph.save(filename)
+        result = ImportGraph.load(filename)
+
+        result.add_import(importer="mypackage.bar", imported="mypackage.baz")
+
+        assert {"mypackage.foo", "mypackage.bar", "mypackage.baz"} == result.modules
+        assert {"mypackage.foo", "mypackage.bar"} == graph.modules
+
+    @pytest.mark.parametrize(
+        "contents",
+        (
+            # Graphs used to be saved using pickle, which could run arbitrary code when loaded.
+            pickle.dumps({"not": "a graph"}),
+            b'{"not": "a graph"}',
+            b"[]",
+            b"not json",
+        ),
+    )
+    def test_raises_value_error_if_file_does_not_contain_graph(self, tmp_path, contents):
+        filename = tmp_path / "not-a-graph.grimp"
+        filename.write_bytes(contents)
+
+        with pytest.raises(
+            ValueError, match=re.escape(f"{filename} does not contain a saved ImportGraph.")
+        ):
+            ImportGraph.load(str(filename))
+
+
+class TestToSqlite:
+    def test_writes_modules(self, tmp_path):
+        graph = ImportGraph()
+        graph.add_module("mypackage.foo")
+        graph.set_module_provenance(
+            "mypackage.foo", package_name="mypackage", filename="/path/to/foo.py", mtime=1.5
+        )
+        graph.add_module("mypackage.bar", is_squashed=True)
+        graph.add_module("django", is_squashed=True, external_kind="third_party")
+        filename = str(tmp_path / "graph.sqlite")
+
+        graph.to_sqlite(filename)
+
+        assert [
+            ("django", 1, "third_party", None, None, None),
+            ("mypackage.bar", 1, None, None, None, None),
+            ("mypackage.foo", 0, None, "mypackage", "/path/to/foo.py", 1.5),
+        ] == self._query(filename, "SELECT * FROM modules ORDER BY name")
+
+    def test_writes_imports_and_details(self, tmp_path):
+        graph = ImportGraph()
+        graph.add_import(
+            importer="mypackage.foo",
+            imported="mypackage.bar",
+            line_number=3,
+            line_contents="from mypackage import bar  # grimp: ignore[one, two]",
+            imported_object="mypackage.bar.baz",
+            ignored_for=("one", "two"),
+            column=4,
+        )
+        graph.add_import(importer="mypackage.bar", imported="mypackage.baz")
+        graph.add_import(
+            importer="mypackage.foo",
+            imported="mypackage.removed",
+            line_number=1,
+            line_contents="import mypackage.removed",
+        )
+        graph.remove_import(importer="mypackage.foo", imported="mypackage.removed")
+        filename = str(tmp_path / "graph.sqlite")
+
+        graph.to_sqlite(filename)
+
+        assert [("mypackage.bar", "mypackage.baz"), ("mypackage.foo", "mypackage.bar")] == (
+            self._query(filename, "SELECT * FROM imports ORDER BY importer")
+        )
+        assert [
+            (
+                "mypackage.foo",
+                "mypackage.bar",
+                3,
+                "from mypackage import bar  # grimp: ignore[one, two]",
+                0,
+                "module",
+                "mypackage.bar.baz",
+                0,
+                "one,two",
+                0,
+                4,
+                None,
+                None,
+                0,
+                0,
+                None,
+            )
+        ] == self._query(filename, "SELECT * FROM import_details")
+
+    def test_replaces_existing_tables(self, tmp_path):
+        filename = str(tmp_path / "graph.sqlite")
+        first_graph = ImportGraph()
+        first_graph.add_import(importer="mypackage.foo", imported="mypackage.bar")
+        first_graph.to_sqlite(filename)
+        second_graph = ImportGraph()
+        second_graph.add_module("mypackage.baz")
+
+        second_graph.to_sqlite(filename)
+
+        assert [("mypackage.baz",)] == self._query(filename, "SELECT name FROM modules")
+        assert [] == self._query(filename, "SELECT * FROM imports")
+
+    def _query(self, filename, sql):
+        connection = sqlite3.connect(filename)
+        try:
+            return connection.execute(sql).fetchall()
+        finally:
+            connection.close()
+
+
+class TestToNetworkxData:
+    def test_returns_node_link_data(self):
+        graph = ImportGraph()
+        graph.add_module("django", is_squashed=True, external_kind="third_party")
+        graph.add_import(
+            importer="mypackage.foo",
+            imported="mypackage.bar",
+            line_number=1,
+            line_contents="import mypackage.bar  # grimp: ignore[one]",
+            ignored_for=("one",),
+        )
+        graph.add_import(importer="mypackage.bar", imported="django")
+        graph.set_module_provenance(
+            "mypackage.foo", package_name="mypackage", filename="/path/to/foo.py", mtime=1.5
+        )
+
+        result = graph.to_networkx_data()
+
+        assert {
+            "directed": True,
+            "multigraph": False,
+            "graph": {},
+            "nodes": [
+                {"id": "django", "is_squashed": True, "external_kind": "third_party"},
+                {"id": "mypackage.bar", "is_squashed": False},
+                {
+                    "id": "mypackage.foo",
+                    "is_squashed": False,
+                    "package_name": "mypackage",
+                    "filename": "/path/to/foo.py",
+                    "mtime": 1.5,
+                },
+            ],
+            "links": [
+                {
+                    "source": "mypackage.bar",
+                    "target": "django",
+                    "weight": 1,
+                    "import_details": [],
+                },
+                {
+                    "source": "mypackage.foo",
+                    "target": "mypackage.bar",
+                    "weight": 1,
+                    "import_details": [
+                        {
+                            "line_number": 1,
+                            "line_contents": "import mypackage.bar  # grimp: ignore[one]",
+                            "is_dynamic": False,
+                            "scope": "module",
+                            "imported_object": None,
+                            "is_type_checking": False,
+                            "ignored_for": ["one"],
+                            "is_cimport": False,
+                            "column": None,
+                            "end_line_number": None,
+                            "platform_condition": None,
+                            "is_optional": False,
+                            "is_lazy": False,
+                            "version_condition": None,
+                        }
+                    ],
+                },
+            ],
+        } == result
+
+    def test_includes_build_info(self):
+        graph = ImportGraph()
+        graph.set_build_info({"package_names": ["mypackage"]})
+
+        result = graph.to_networkx_data()
+
+        assert {"build_info": {"package_names": ["mypackage"]}} == result["graph"]
+
+    def test_can_be_loaded_by_networkx(self):
+        graph = ImportGraph()
+        graph.add_import(importer="mypackage.foo", imported="mypackage.bar")
+        data = graph.to_networkx_data()
+
+        networkx_graph = networkx.node_link_graph(dict(data, edges=data["links"]))
+
+        assert {"mypackage.foo", "mypackage.bar"} == set(networkx_graph.nodes)
+        assert [("mypackage.foo", "mypackage.bar")] == list(networkx_graph.edges)
+
+
+class TestFromNetworkxData:
+    def test_round_trips_graph(self):
+        graph = ImportGraph()
+        graph.add_module("django", is_squashed=True, external_kind="third_party")
+        graph.add_import(
+            importer="mypackage.foo",
+            imported="mypackage.bar",
+            line_number=1,
+            line_contents="import mypackage.bar  # grimp: ignore[one]",
+            ignored_for=("one",),
+        )
+        graph.add_import(importer="mypackage.bar", imported="django")
+        graph.set_module_provenance(
+            "mypackage.foo", package_name="mypackage", filename="/path/to/foo.py", mtime=1.5
+        )
+        graph.set_build_info({"package_names": ["mypackage"]})
+
+        result = ImportGraph.from_networkx_data(graph.to_networkx_data())
+
+        assert graph.modules == result.modules
+        assert 2 == result.count_imports()
+        assert graph.get_import_details(
+            importer="mypackage.foo", imported="mypackage.bar"
+        ) == result.get_import_details(importer="mypackage.foo", imported="mypackage.bar")
+        assert result.is_module_squashed("django")
+        assert {"django"} == result.find_external_modules(kind="third_party")
+        assert graph.get_module_provenance("mypackage.foo") == result.get_module_provenance(
+            "mypackage.foo"
+        )
+        assert {"package_names": ["mypackage"]} == result.build_info()
+
+    def test_reads_data_from_networkx(self):
+        networkx_graph = networkx.DiGraph()
+        networkx_graph.add_edge("mypackage.foo", "mypackage.bar")
+
+        result = ImportGraph.from_networkx_data(networkx.node_link_data(networkx_graph))
+
+        assert {"mypackage.foo", "mypackage.bar"} == result.modules
+        assert result.direct_import_exists(importer="mypackage.foo", imported="mypackage.bar")
+        assert result.build_info() is None
+
+
+class TestFromEdgeList:
+    def test_adds_imports(self):
+        result = ImportGraph.from_edge_list(
+            [("mypackage.foo", "mypackage.bar"), ("mypackage.bar", "mypackage.baz")]
+        )
+
+        assert {"mypackage.foo", "mypackage.bar", "mypackage.baz"} == result.modules
+        assert 2 == result.count_imports()
+        assert result.chain_exists(importer="mypackage.foo", imported="mypackage.baz")
+
+    def test_accepts_generator(self):
+        result = ImportGraph.from_edge_list(
+            (f"mypackage.{name}", "mypackage.base") for name in ("foo", "bar")
+        )
+
+        assert {"mypackage.foo", "mypackage.bar"} == result.find_modules_that_directly_import(
+            "mypackage.base"
+        )
+
+
+class TestFromPydeps:
+    def test_reads_modules_and_imports(self, tmp_path):
+        filename = tmp_path / "pydeps.json"
+        filename.write_text(
+            json.dumps(
+                {
+                    "mypackage": {
+                        "bacon": 1,
+                        "imported_by": ["mypackage.foo"],
+                        "name": "mypackage",
+                        "path": "/path/to/mypackage/__init__.py",
+                    },
+                    "mypackage.foo": {
+                        "bacon": 0,
+                        "imports": ["mypackage", "mypackage.bar"],
+                        "name": "mypackage.foo",
+                        "path": "/path/to/mypackage/foo.py",
+                    },
+                    "mypackage.bar": {
+                        "bacon": 1,
+                        "imported_by": ["mypackage.foo"],
+                        "name": "mypackage.bar",
+                        "path": "/path/to/mypackage/bar.py",
+                    },
+                    "mypackage.unimported": {
+                        "bacon": 2,
+                        "name": "mypackage.unimported",
+                        "path": "/path/to/mypackage/unimported.py",
+                    },
+                }
+            ),
+            encoding="utf-8",
+        )
+
+        result = ImportGraph.from_pydeps(str(filename))
+
+        assert {
+            "mypackage",
+            "mypackage.foo",
+            "mypackage.bar",
+            "mypackage.unimported",
+        } == result.modules
+        assert {"mypackage", "mypackage.bar"} == result.find_modules_directly_imported_by(
+            "mypackage.foo"
+        )
+        assert 2 == result.count_imports()
+
+
+class TestFromSnakefood:
+    def test_reads_modules_and_imports(self, tmp_path):
+        filename = tmp_path / "deps.txt"
+        filename.write_text(
+            "\n".join(
+                [
+                    "(('/path/to', 'mypackage/__init__.py'), (None, None))",
+                    "(('/path/to', 'mypackage/foo.py'), ('/path/to', 'mypackage/__init__.py'))",
+                    "(('/path/to', 'mypackage/foo.py'), ('/path/to', 'mypackage/bar/__init__.py'))",
+                    "(('/path/to', 'mypackage/bar/__init__.py'), ('/other', 'six.py'))",
+                    "(('/path/to', 'mypackage/unimported.py'), (None, None))",
+                    "",
+                ]
+            ),
+            encoding="utf-8",
+        )
+
+        result = ImportGraph.from_snakefood(str(filename))
+
+        assert {
+            "mypackage",
+            "mypackage.foo",
+            "mypackage.bar",
+            "mypackage.unimported",
+            "six",
+        } == result.modules
+        assert {"mypackage", "mypackage.bar"} == result.find_modules_directly_imported_by(
+            "mypackage.foo"
+        )
+        assert {"six"} == result.find_modules_directly_imported_by("mypackage.bar")
+        assert 3 == result.count_imports()
+
+
+class TestToJson:
+    def test_contains_networkx_data(self):
+        graph = ImportGraph()
+        graph.add_import(
+            importer="mypackage.foo",
+            imported="mypackage.bar",
+            line_number=1,
+            line_contents="import mypackage.bar",
+        )
+        graph.set_module_provenance(
+            "mypackage.foo", package_name="mypackage", filename="/path/to/foo.py", mtime=1.5
+        )
+        graph.set_build_info({"package_names": ["mypackage"]})
+
+        result = graph.to_json()
+
+        assert graph.to_networkx_data() == json.loads(result)
+
+    def test_canonical_json_is_independent_of_build_order(self):
+        first_graph = self._build_graph(
+            imports=[
+                ("mypackage.foo", "mypackage.bar", 3),
+                ("mypackage.foo", "mypackage.bar", 1),
+                ("mypackage.baz", "mypackage.foo", 2),
+            ],
+            mtime=1.5,
+        )
+        second_graph = self._build_graph(
+            imports=[
+                ("mypackage.baz", "mypackage.foo", 2),
+                ("mypackage.foo", "mypackage.bar", 1),
+                ("mypackage.foo", "mypackage.bar", 3),
+            ],
+            mtime=2.5,
+        )
+
+        assert first_graph.to_json(canonical=True) == second_graph.to_json(canonical=True)
+        assert first_graph.to_json() != second_graph.to_json()
+
+    def test_canonical_json_is_compact_and_sorted(self):
+        graph = ImportGraph()
+        graph.add_import(importer="mypackage.foo", imported="mypackage.bar")
+
+        result = graph.to_json(canonical=True)
+
+        assert (
+            '{"directed":true,"graph":{},"links":[{"import_details":[],'
+            '"source":"mypackage.foo","target":"mypackage.bar","weight":1}],'
+            '"multigraph":false,"nodes":[{"id":"mypackage.bar","is_squashed":false},'
+            '{"id":"mypackage.foo","is_squashed":false}]}'
+        ) == result
+
+    def _build_graph(self, imports, mtime):
+        graph = ImportGraph()
+        for importer, imported, line_number in imports:
+            graph.add_import(
+                importer=importer,
+                imported=imported,
+                line_number=line_number,
+                line_contents=f"import {imported}",
+            )
+        for module in graph.modules:
+            graph.set_module_provenance(
+                module, package_name="mypackage", filename=f"/{module}.py", mtime=mtime
+            )
+        graph.set_build_info({"timestamp": str(mtime)})
+        return graph
+
+
+class TestWriteJson:
+    @pytest.mark.parametrize("canonical", (True, False))
+    def test_writes_same_json_as_to_json(self, canonical, tmp_path):
+        graph = ImportGraph()
+        graph.add_import(
+            importer="mypackage.foo",
+            imported="mypackage.bar",
+            line_number=1,
+            line_contents="import mypackage.bar",
+        )
+        graph.add_import(importer="mypackage.bar", imported="mypackage.baz")
+        graph.add_module("mypackage.unimported")
+        graph.set_build_info({"package_names": ["mypackage"]})
+        filename = tmp_path / "graph.json"
+
+        with open(filename, "w", encoding="utf-8") as json_file:
+            graph.write_json(json_file, canonical=canonical)
+
+        assert graph.to_json(canonical=canonical) == filename.read_text(encoding="utf-8")
+
+    def test_writes_empty_graph(self):
+        graph = ImportGraph()
+        json_file = io.StringIO()
+
+        graph.write_json(json_file)
+
+        assert {
+            "directed": True,
+            "multigraph": False,
+            "graph": {},
+            "nodes": [],
+            "links": [],
+        } == json.loads(json_file.getvalue())
+
+
+class TestToHtml:
+    def test_draws_root_packages_by_default(self, tmp_path):
+        graph = ImportGraph()
+        graph.add_module("mypackage")
+        graph.add_module("anotherpackage")
+        graph.add_module("django", is_squashed=True, external_kind="third_party")
+        graph.add_import(importer="mypackage.foo", imported="anotherpackage.bar")
+        graph.add_import(importer="mypackage.foo.one", imported="anotherpackage.baz")
+        graph.add_import(importer="mypackage.foo", imported="mypackage.bar")
+        graph.add_import(importer="anotherpackage.bar", imported="django")
+        filename = tmp_path / "graph.html"
+
+        graph.to_html(str(filename))
+
+        data = self._read_data(filename)
+        assert ["anotherpackage", "django", "mypackage"] == [
+            node["id"] for node in data["nodes"]
+        ]
+        assert ["anotherpackage", "django", "mypackage"] == [
+            node["label"] for node in data["nodes"]
+        ]
+        assert "third_party" == data["nodes"][1]["external_kind"]
+        assert [
+            {"source": "anotherpackage", "target": "django", "weight": 1},
+            {"source": "mypackage", "target": "anotherpackage", "weight": 2},
+        ] == data["links"]
+
+    def test_draws_children_of_container(self, tmp_path):
+        graph = ImportGraph()
+        for module in ("mypackage", "mypackage.foo", "mypackage.bar", "mypackage.baz"):
+            graph.add_module(module)
+        graph.add_import(importer="mypackage.foo.one", imported="mypackage.bar.two")
+        graph.add_import(importer="mypackage.foo", imported="mypackage.bar")
+        graph.add_import(importer="mypackage.foo.one", imported="mypackage.foo.two")
+        graph.add_import(importer="mypackage.baz", imported="mypackage")
+        graph.add_import(importer="mypackage.baz", imported="anotherpackage")
+        filename = tmp_path / "graph.html"
+
+        graph.to_html(str(filename), container="mypackage")
+
+        data = self._read_data(filename)
+        assert ["mypackage.bar", "mypackage.baz", "mypackage.foo"] == [
+            node["id"] for node in data["nodes"]
+        ]
+        assert ["bar", "baz", "foo"] == [node["label"] for node in data["nodes"]]
+        assert [{"source": "mypackage.foo", "target": "mypackage.bar", "weight": 2}] == data[
+            "links"
+        ]
+        assert "<title>mypackage</title>" in filename.read_text(encoding="utf-8")
+
+    def test_escapes_data_within_script(self, tmp_path):
+        graph = ImportGraph()
+        graph.add_module("</script><script>alert(1)</script>")
+        filename = tmp_path / "graph.html"
+
+        graph.to_html(str(filename))
+
+        assert "alert(1)</script>" not in filename.read_text(encoding="utf-8")
+        assert ["</script><script>alert(1)</script>"] == [
+            node["id"] for node in self._read_data(filename)["nodes"]
+        ]
+
+    def test_raises_value_error_for_squashed_container(self, tmp_path):
+        graph = ImportGraph()
+        graph.add_module("mypackage", is_squashed=True)
+
+        with pytest.raises(ValueError, match="Cannot visualise the contents of a squashed module."):
+            graph.to_html(str(tmp_path / "graph.html"), container="mypackage")
+
+    def test_raises_module_not_present_for_missing_container(self, tmp_path):
+        graph = ImportGraph()
+
+        with pytest.raises(ModuleNotPresent):
+            graph.to_html(str(tmp_path / "graph.html"), container="mypackage")
+
+    def _read_data(self, filename):
+        match = re.search(
+            r'<script type="application/json" id="graph-data">(.*?)</script>',
+            filename.read_text(encoding="utf-8"),
+        )
+        return json.loads(match.group(1))
+
+
+class TestExportNeighbourhood:
+    @pytest.mark.parametrize(
+        "radius, expected_modules",
+        (
+            (0, {"mypackage.c"}),
+            (1, {"mypackage.b", "mypackage.c", "mypackage.d", "mypackage.x"}),
+            (
+                2,
+                {
+                    "mypackage.a",
+                    "mypackage.b",
+                    "mypackage.c",
+                    "mypackage.d",
+                    "mypackage.e",
+                    "mypackage.x",
+                },
+            ),
+        ),
+    )
+    def test_exports_modules_within_radius(self, radius, expected_modules, tmp_path):
+        graph = ImportGraph.from_edge_list(
+            [
+                ("mypackage.a", "mypackage.b"),
+                ("mypackage.b", "mypackage.c"),
+                ("mypackage.c", "mypackage.d"),
+                ("mypackage.d", "mypackage.e"),
+                ("mypackage.e", "mypackage.f"),
+                # Imported by the module, rather than importing it.
+                ("mypackage.x", "mypackage.c"),
+            ]
+        )
+        filename = tmp_path / "neighbourhood.json"
+
+        graph.export_neighbourhood("mypackage.c", radius, "json", str(filename))
+
+        data = json.loads(filename.read_text(encoding="utf-8"))
+        assert expected_modules == {node["id"] for node in data["nodes"]}
+        assert all(
+            link["source"] in expected_modules and link["target"] in expected_modules
+            for link in data["links"]
+        )
+
+    def test_includes_metadata(self, tmp_path):
+        graph = ImportGraph()
+        graph.add_module("django", is_squashed=True, external_kind="third_party")
+        graph.add_import(
+            importer="mypackage.foo",
+            imported="mypackage.bar",
+            line_number=1,
+            line_contents="import mypackage.bar",
+        )
+        graph.add_import(importer="mypackage.foo", imported="django")
+        graph.add_import(importer="mypackage.baz", imported="mypackage.bar")
+        graph.set_module_provenance(
+            "mypackage.foo", package_name="mypackage", filename="/path/to/foo.py", mtime=1.5
+        )
+        graph.set_build_info({"package_names": ["mypackage"]})
+        filename = str(tmp_path / "neighbourhood.grimp")
+        expected = ImportGraph()
+        expected.add_module("django", is_squashed=True, external_kind="third_party")
+        expected.add_import(
+            importer="mypackage.foo",
+            imported="mypackage.bar",
+            line_number=1,
+            line_contents="import mypackage.bar",
+        )
+        expected.add_import(importer="mypackage.foo", imported="django")
+        expected.set_module_provenance(
+            "mypackage.foo", package_name="mypackage", filename="/path/to/foo.py", mtime=1.5
+        )
+
+        graph.export_neighbourhood("mypackage.foo", 1, "json", filename)
+
+        with open(filename, encoding="utf-8") as json_file:
+            assert expected.to_networkx_data() == json.load(json_file)
+
+    @pytest.mark.parametrize("format", ("csv", "gexf", "html", "sqlite"))
+    def test_exports_in_other_formats(self, format, tmp_path):
+        graph = ImportGraph.from_edge_list([("mypackage.foo", "mypackage.bar")])
+        filename = tmp_path / f"neighbourhood.{format}"
+
+        graph.export_neighbourhood("mypackage.foo", 1, format, str(filename))
+
+        assert filename.stat().st_size > 0
+
+    def test_raises_module_not_present(self, tmp_path):
+        graph = ImportGraph()
+
+        with pytest.raises(ModuleNotPresent):
+            graph.export_neighbourhood("mypackage.foo", 1, "json", str(tmp_path / "n.json"))
+
+    def test_raises_value_error_for_negative_radius(self, tmp_path):
+        graph = ImportGraph.from_edge_list([("mypackage.foo", "mypackage.bar")])
+
+        with pytest.raises(ValueError, match="Radius must not be negative."):
+            graph.export_neighbourhood("mypackage.foo", -1, "json", str(tmp_path / "n.json"))
+
+    def test_raises_value_error_for_unknown_format(self, tmp_path):
+        graph = ImportGraph.from_edge_list([("mypackage.foo", "mypackage.bar")])
+
+        with pytest.raises(ValueError, match="Unknown export format dot."):
+            graph.export_neighbourhood("mypackage.foo", 1, "dot", str(tmp_path / "n.dot"))
+
+
+class TestRuntimeImports:
+    def test_runtime_imports_are_kept_separately(self):
+        graph = ImportGraph.from_edge_list([("mypackage.foo", "mypackage.bar")])
+
+        graph.add_runtime_import(importer="mypackage.bar", imported="mypackage.baz")
+
+        assert {("mypackage.bar", "mypackage.baz")} == graph.find_runtime_imports()
+        assert {"mypackage.foo", "mypackage.bar"} == graph.modules
+        assert 1 == graph.count_imports()
+
+    def test_find_runtime_imports_can_exclude_static_imports(self):
+        graph = ImportGraph.from_edge_list([("mypackage.foo", "mypackage.bar")])
+        graph.add_runtime_import(importer="mypackage.foo", imported="mypackage.bar")
+        graph.add_runtime_import(importer="mypackage.bar", imported="mypackage.baz")
+
+        result = graph.find_runtime_imports(exclude_static=True)
+
+        assert {("mypackage.bar", "mypackage.baz")} == result
+
+    def test_merge_runtime_imports(self):
+        graph = ImportGraph()
+        graph.add_import(
+            importer="mypackage.foo",
+            imported="mypackage.bar",
+            line_number=1,
+            line_contents="import mypackage.bar",
+        )
+        graph.add_runtime_import(importer="mypackage.foo", imported="mypackage.bar")
+        graph.add_runtime_import(importer="mypackage.bar", imported="mypackage.baz")
+
+        graph.merge_runtime_imports()
+
+        assert {"mypackage.foo", "mypackage.bar", "mypackage.baz"} == graph.modules
+        assert 2 == graph.count_imports()
+        assert graph.chain_exists(importer="mypackage.foo", imported="mypackage.baz")
+        assert 1 == len(
+            graph.get_import_details(importer="mypackage.foo", imported="mypackage.bar")
+        )
+
+    def test_read_runtime_trace(self, tmp_path):
+        filename = tmp_path / "trace.jsonl"
+        filename.write_text(
+            '{"importer": "mypackage.foo", "imported": "mypackage.bar"}\n'
+            "\n"
+            '{"importer": "mypackage.bar", "imported": "mypackage.baz"}\n'
+            '{"importer": "mypackage.foo", "imported": "mypackage.bar"}\n',
+            encoding="utf-8",
+        )
+        graph = ImportGraph()
+
+        graph.read_runtime_trace(str(filename))
+
+        assert {
+            ("mypackage.foo", "mypackage.bar"),
+            ("mypackage.bar", "mypackage.baz"),
+        } == graph.find_runtime_imports()
+
+    def test_remove_module_removes_its_runtime_imports(self):
+        graph = ImportGraph.from_edge_list([("mypackage.foo", "mypackage.bar")])
+        graph.add_runtime_import(importer="mypackage.foo", imported="mypackage.bar")
+        graph.add_runtime_import(importer="mypackage.bar", imported="mypackage.baz")
+        graph.add_runtime_import(importer="mypackage.foo", imported="mypackage.baz")
+
+        graph.remove_module("mypackage.bar")
+        graph.merge_runtime_imports()
+
+        assert {("mypackage.foo", "mypackage.baz")} == graph.find_runtime_imports()
+        assert {"mypackage.foo", "mypackage.baz"} == graph.modules
+
+    def test_squash_module_squashes_runtime_imports(self):
+        graph = ImportGraph.from_edge_list(
+            [("mypackage.foo", "mypackage.bar"), ("mypackage.foo", "mypackage.foo.one")]
+        )
+        graph.add_runtime_import(importer="mypackage.foo.one", imported="mypackage.baz")
+        graph.add_runtime_import(importer="mypackage.bar", imported="mypackage.foo.one")
+        graph.add_runtime_import(importer="mypackage.foo", imported="mypackage.foo.one")
+
+        graph.squash_module("mypackage.foo")
+        graph.merge_runtime_imports()
+
+        assert {
+            ("mypackage.foo", "mypackage.baz"),
+            ("mypackage.bar", "mypackage.foo"),
+        } == graph.find_runtime_imports()
+        assert {"mypackage.foo", "mypackage.bar", "mypackage.baz"} == graph.modules
@@ -1,6 +1,14 @@
+import ast
+
 import pytest  # type: ignore
-from grimp.adaptors.importscanner import ImportScanner
-from grimp.domain.valueobjects import DirectImport, Module
+from grimp import exceptions
+from grimp.adaptors.importscanner import ImportScanner, _get_end_line_number
+from grimp.domain.valueobjects import (
+    DirectImport,
+    ExternalImportGranularity,
+    ImportScope,
+    Module,
+)
 
 from tests.adaptors.filesystem import FakeFileSystem
 
@@ -176,6 +184,57 @@ def test_absolute_from_imports(include_external_packages, expected_result):
     assert expected_result == result
 
 
+@pytest.mark.parametrize(
+    "external_import_granularity, expected_imports",
+    (
+        (
+            ExternalImportGranularity.ROOT,
+            {("os", None), ("django", None), ("importlib", None)},
+        ),
+        (
+            ExternalImportGranularity.FULL,
+            {
+                ("os.path", None),
+                ("django.db.models", "django.db.models.JSONField"),
+                ("django.db.models", "django.db.models.Model"),
+                ("django.contrib", None),
+                ("importlib", None),
+                ("external.blue", None),
+            },
+        ),
+    ),
+)
+def test_external_import_granularity(external_import_granularity, expected_imports):
+    all_modules = {Module("foo"), Module("foo.one")}
+    file_system = FakeFileSystem(
+        content_map={
+            "/path/to/foo/one.py": """
+                import os.path
+                from django.db.models import JSONField, Model
+                from django.contrib import *
+                import importlib
+                importlib.import_module("external.blue")
+            """
+        },
+    )
+    import_scanner = ImportScanner(
+        modules_by_package_directory={"/path/to/foo": all_modules},
+        file_system=file_system,
+        include_external_packages=True,
+        include_dynamic_imports=True,
+        external_import_granularity=external_import_granularity,
+        include_imported_objects=True,
+    )
+
+    result = import_scanner.scan_for_imports(Module("foo.one"))
+
+    if external_import_granularity == ExternalImportGranularity.ROOT:
+        expected_imports.add(("external", None))
+    assert {
+        (direct_import.imported.name, direct_import.imported_object) for direct_import in result
+    } == expected_imports
+
+
 def test_relative_from_imports():
     all_modules = {
         Module("foo.one.blue"),
@@ -330,6 +389,51 @@ def test_trims_to_known_modules_within_init_file():
     }
 
 
+@pytest.mark.parametrize("include_imported_objects", (True, False))
+def test_records_each_imported_object_if_include_imported_objects(include_imported_objects):
+    all_modules = {
+        Module("foo"),
+        Module("foo.one"),
+        Module("foo.two"),
+        Module("foo.two.yellow"),
+    }
+    file_system = FakeFileSystem(
+        content_map={
+            "/path/to/foo/one.py": "from foo.two import yellow, blue_function, RED",
+        },
+    )
+
+    import_scanner = ImportScanner(
+        modules_by_package_directory={"/path/to/foo": all_modules},
+        file_system=file_system,
+        include_imported_objects=include_imported_objects,
+    )
+
+    result = import_scanner.scan_for_imports(Module("foo.one"))
+
+    line_contents = "from foo.two import yellow, blue_function, RED"
+    imported_objects = (
+        ["foo.two.blue_function", "foo.two.RED"] if include_imported_objects else [None]
+    )
+    assert result == {
+        DirectImport(
+            importer=Module("foo.one"),
+            imported=Module("foo.two.yellow"),
+            line_number=1,
+            line_contents=line_contents,
+        ),
+    } | {
+        DirectImport(
+            importer=Module("foo.one"),
+            imported=Module("foo.two"),
+            line_number=1,
+            line_contents=line_contents,
+            imported_object=imported_object,
+        )
+        for imported_object in imported_objects
+    }
+
+
 def test_trims_whitespace_from_start_of_line_contents():
     all_modules = {Module("foo"), Module("foo.one"), Module("foo.two")}
     file_system = FakeFileSystem(
@@ -360,6 +464,7 @@ def test_trims_whitespace_from_start_of_line_contents():
             imported=Module("foo.two"),
             line_number=2,
             line_contents="from . import two",
+            scope=ImportScope.FUNCTION,
         )
     }
 
@@ -404,3 +509,903 @@ def test_scans_multiple_packages(statement):
             line_contents=statement,
         ),
     } == result
+
+
+@pytest.mark.parametrize(
+    "source, expected_scope",
+    (
+        ("import foo.two", ImportScope.MODULE),
+        ("if True:\n    import foo.two", ImportScope.MODULE),
+        ("try:\n    import foo.two\nexcept ImportError:\n    pass", ImportScope.MODULE),
+        ("def f():\n    import foo.two", ImportScope.FUNCTION),
+        ("async def f():\n    import foo.two", ImportScope.FUNCTION),
+        ("class A:\n    import foo.two", ImportScope.CLASS),
+        ("class A:\n    def f(self):\n        import foo.two", ImportScope.FUNCTION),
+        ("def f():\n    class A:\n        import foo.two", ImportScope.CLASS),
+    ),
+)
+def test_records_scope(source, expected_scope):
+    all_modules = {Module("foo"), Module("foo.one"), Module("foo.two")}
+    file_system = FakeFileSystem(content_map={"/path/to/foo/one.py": source})
+    import_scanner = ImportScanner(
+        modules_by_package_directory={"/path/to/foo": all_modules},
+        file_system=file_system,
+    )
+
+    result = import_scanner.scan_for_imports(Module("foo.one"))
+
+    assert [expected_scope] == [direct_import.scope for direct_import in result]
+
+
+@pytest.mark.parametrize(
+    "source, type_checking_guards, expected_is_type_checking",
+    (
+        ("import foo.two", (), False),
+        ("if TYPE_CHECKING:\n    import foo.two", (), True),
+        ("if typing.TYPE_CHECKING:\n    import foo.two", (), True),
+        ("if TYPE_CHECKING:\n    if True:\n        import foo.two", (), True),
+        ("def f():\n    if TYPE_CHECKING:\n        import foo.two", (), True),
+        ("if TYPE_CHECKING:\n    pass\nelse:\n    import foo.two", (), False),
+        ("if not TYPE_CHECKING:\n    import foo.two", (), False),
+        ("if MYPY:\n    import foo.two", (), False),
+        ("if MYPY:\n    import foo.two", ("MYPY",), True),
+        ("if compat.TYPE_CHECKING:\n    import foo.two", ("compat.TYPE_CHECKING",), True),
+        ("if TYPE_CHECKING:\n    import foo.two", ("MYPY",), True),
+    ),
+)
+def test_records_whether_type_checking(
+    source, type_checking_guards, expected_is_type_checking
+):
+    all_modules = {Module("foo"), Module("foo.one"), Module("foo.two")}
+    file_system = FakeFileSystem(content_map={"/path/to/foo/one.py": source})
+    import_scanner = ImportScanner(
+        modules_by_package_directory={"/path/to/foo": all_modules},
+        file_system=file_system,
+        type_checking_guards=type_checking_guards,
+    )
+
+    result = import_scanner.scan_for_imports(Module("foo.one"))
+
+    assert [expected_is_type_checking] == [
+        direct_import.is_type_checking for direct_import in result
+    ]
+
+
+@pytest.mark.parametrize(
+    "source, expected_ignored_for",
+    (
+        ("import foo.two", ()),
+        ("import foo.two  # grimp: ignore[my-contract]", ("my-contract",)),
+        ("import foo.two  #grimp:ignore[one, two]", ("one", "two")),
+        ("import foo.two  # noqa  # grimp: ignore[one]  # Some explanation.", ("one",)),
+        ("from foo import (  # grimp: ignore[one]\n    two,\n)", ("one",)),
+        ("from foo import (\n    two,\n)  # grimp: ignore[one]", ("one",)),
+    ),
+)
+def test_records_ignore_directive(source, expected_ignored_for):
+    all_modules = {Module("foo"), Module("foo.one"), Module("foo.two")}
+    file_system = FakeFileSystem(content_map={"/path/to/foo/one.py": source})
+    import_scanner = ImportScanner(
+        modules_by_package_directory={"/path/to/foo": all_modules},
+        file_system=file_system,
+    )
+
+    result = import_scanner.scan_for_imports(Module("foo.one"))
+
+    assert [expected_ignored_for] == [direct_import.ignored_for for direct_import in result]
+
+
+@pytest.mark.parametrize(
+    "source",
+    (
+        "import foo.two  # grimp: ignore",
+        "import foo.two  # grimp:ignore",
+        "from foo import two  # noqa  # grimp: ignore",
+        "def f():\n    import foo.two  # grimp: ignore",
+        "from foo import (\n    two,\n)  # grimp: ignore",
+        "from foo import (\n    two,  # grimp: ignore\n)",
+    ),
+)
+def test_ignore_directive_excludes_import(source):
+    all_modules = {Module("foo"), Module("foo.one"), Module("foo.two"), Module("foo.three")}
+    file_system = FakeFileSystem(
+        content_map={"/path/to/foo/one.py": f"{source}\nimport foo.three"}
+    )
+    import_scanner = ImportScanner(
+        modules_by_package_directory={"/path/to/foo": all_modules},
+        file_system=file_system,
+    )
+
+    result = import_scanner.scan_for_imports(Module("foo.one"))
+
+    assert {Module("foo.three")} == {direct_import.imported for direct_import in result}
+
+
+@pytest.mark.parametrize("max_file_size", (None, 14, 100))
+def test_scans_files_up_to_max_file_size(max_file_size):
+    all_modules = {Module("foo"), Module("foo.one"), Module("foo.two")}
+    file_system = FakeFileSystem(content_map={"/path/to/foo/one.py": "import foo.two"})
+    import_scanner = ImportScanner(
+        modules_by_package_directory={"/path/to/foo": all_modules},
+        file_system=file_system,
+        max_file_size=max_file_size,
+    )
+
+    result = import_scanner.scan_for_imports(Module("foo.one"))
+
+    assert {Module("foo.two")} == {direct_import.imported for direct_import in result}
+
+
+def test_raises_if_file_larger_than_max_file_size():
+    all_modules = {Module("foo"), Module("foo.one"), Module("foo.two")}
+    file_system = FakeFileSystem(content_map={"/path/to/foo/one.py": "import foo.two"})
+    import_scanner = ImportScanner(
+        modules_by_package_directory={"/path/to/foo": all_modules},
+        file_system=file_system,
+        max_file_size=13,
+    )
+
+    with pytest.raises(exceptions.SourceFileTooLarge) as excinfo:
+        import_scanner.scan_for_imports(Module("foo.one"))
+
+    assert excinfo.value == exceptions.SourceFileTooLarge(
+        filename="/path/to/foo/one.py", size=14, max_file_size=13
+    )
+
+
+@pytest.mark.parametrize(
+    "source, expected_line_number, expected_column, expected_end_line_number",
+    (
+        ("import foo.two", 1, 0, 1),
+        ("from foo import (\n    two,\n)", 1, 0, 3),
+        ("from foo import \\\n    two", 1, 0, 2),
+        ("def f():\n    from . import (  # comment (\n        two\n    )", 2, 4, 4),
+    ),
+)
+def test_records_position(
+    source, expected_line_number, expected_column, expected_end_line_number
+):
+    all_modules = {Module("foo"), Module("foo.one"), Module("foo.two")}
+    file_system = FakeFileSystem(content_map={"/path/to/foo/one.py": source})
+    import_scanner = ImportScanner(
+        modules_by_package_directory={"/path/to/foo": all_modules},
+        file_system=file_system,
+    )
+
+    [direct_import] = import_scanner.scan_for_imports(Module("foo.one"))
+
+    assert direct_import.line_number == expected_line_number
+    assert direct_import.column == expected_column
+    assert direct_import.end_line_number == expected_end_line_number
+
+
+@pytest.mark.parametrize(
+    "source, expected_end_line_number",
+    (
+        ("import foo", 1),
+        ("from foo import (\n    bar,\n    baz,\n)\nimport foo", 4),
+        ("from foo import bar, \\\n    baz", 2),
+    ),
+)
+def test_get_end_line_number_without_end_lineno(source, expected_end_line_number):
+    # Versions of Python before 3.8 don't set end_lineno on nodes.
+    node = ast.parse(source).body[0]
+    if hasattr(node, "end_lineno"):
+        del node.end_lineno
+
+    assert _get_end_line_number(node, source.splitlines()) == expected_end_line_number
+
+
+@pytest.mark.parametrize(
+    "source, expected_platform_condition",
+    (
+        ("import foo.two", None),
+        ('if sys.platform == "win32":\n    import foo.two', 'sys.platform == "win32"'),
+        ("if os.name != 'nt':  # Not Windows.\n    import foo.two", "os.name != 'nt'"),
+        (
+            'if platform.system() == "Linux":\n    pass\nelse:\n    import foo.two',
+            'not (platform.system() == "Linux")',
+        ),
+        (
+            'if sys.platform == "win32":\n    pass\nelif sys.platform == "darwin":\n'
+            "    import foo.two",
+            'sys.platform == "darwin"',
+        ),
+        (
+            'def f():\n    if sys.platform.startswith("linux"):\n        import foo.two',
+            'sys.platform.startswith("linux")',
+        ),
+        ("if DEBUG:\n    import foo.two", None),
+    ),
+)
+def test_records_platform_condition(source, expected_platform_condition):
+    all_modules = {Module("foo"), Module("foo.one"), Module("foo.two")}
+    file_system = FakeFileSystem(content_map={"/path/to/foo/one.py": source})
+    import_scanner = ImportScanner(
+        modules_by_package_directory={"/path/to/foo": all_modules},
+        file_system=file_system,
+    )
+
+    [direct_import] = import_scanner.scan_for_imports(Module("foo.one"))
+
+    assert direct_import.platform_condition == expected_platform_condition
+
+
+@pytest.mark.parametrize(
+    "source, expected_version_condition",
+    (
+        ("import foo.two", None),
+        ("if sys.version_info >= (3, 8):\n    import foo.two", "sys.version_info >= (3, 8)"),
+        (
+            "if sys.version_info < (3, 7):\n    pass\nelse:\n    import foo.two",
+            "not (sys.version_info < (3, 7))",
+        ),
+        ("if sys.version_info[0] == 2:\n    import foo.two", "sys.version_info[0] == 2"),
+        ("if sys.hexversion >= 0x030800F0:\n    import foo.two", "sys.hexversion >= 0x030800F0"),
+        ('if sys.platform == "win32":\n    import foo.two', None),
+    ),
+)
+def test_records_version_condition(source, expected_version_condition):
+    all_modules = {Module("foo"), Module("foo.one"), Module("foo.two")}
+    file_system = FakeFileSystem(content_map={"/path/to/foo/one.py": source})
+    import_scanner = ImportScanner(
+        modules_by_package_directory={"/path/to/foo": all_modules},
+        file_system=file_system,
+    )
+
+    [direct_import] = import_scanner.scan_for_imports(Module("foo.one"))
+
+    assert direct_import.version_condition == expected_version_condition
+
+
+@pytest.mark.parametrize(
+    "source, expected_is_optional",
+    (
+        ("import foo.two", False),
+        ("try:\n    import foo.two\nexcept ImportError:\n    pass", True),
+        ("try:\n    import foo.two\nexcept (ValueError, ModuleNotFoundError):\n    pass", True),
+        ("try:\n    import foo.two\nexcept:\n    pass", True),
+        ("try:\n    import foo.two\nexcept ValueError:\n    pass", False),
+        ("try:\n    pass\nexcept ImportError:\n    import foo.two", False),
+        ("with contextlib.suppress(ImportError):\n    import foo.two", True),
+        ("with suppress(KeyError, ImportError):\n    import foo.two", True),
+        ("with suppress(KeyError):\n    import foo.two", False),
+        ("with open('x'):\n    import foo.two", False),
+    ),
+)
+def test_records_whether_optional(source, expected_is_optional):
+    all_modules = {Module("foo"), Module("foo.one"), Module("foo.two")}
+    file_system = FakeFileSystem(content_map={"/path/to/foo/one.py": source})
+    import_scanner = ImportScanner(
+        modules_by_package_directory={"/path/to/foo": all_modules},
+        file_system=file_system,
+    )
+
+    [direct_import] = import_scanner.scan_for_imports(Module("foo.one"))
+
+    assert direct_import.is_optional == expected_is_optional
+
+
+@pytest.mark.parametrize(
+    "source, expected_unresolved",
+    (
+        ("from . import two", None),
+        ("from .missing import blue", "foo.missing.blue"),
+        ("from .... import green", ".green"),
+    ),
+)
+def test_records_warnings_for_unresolved_imports(source, expected_unresolved):
+    all_modules = {Module("foo"), Module("foo.one"), Module("foo.two")}
+    file_system = FakeFileSystem(content_map={"/path/to/foo/one.py": source})
+    import_scanner = ImportScanner(
+        modules_by_package_directory={"/path/to/foo": all_modules},
+        file_system=file_system,
+    )
+
+    import_scanner.scan_for_imports(Module("foo.one"))
+
+    if expected_unresolved is None:
+        assert [] == import_scanner.warnings
+        assert {} == import_scanner.unresolved_imports
+    else:
+        assert {
+            Module("foo.one"): [{"name": expected_unresolved, "line_number": 1}]
+        } == import_scanner.unresolved_imports
+        [warning] = import_scanner.warnings
+        assert isinstance(warning, exceptions.UnresolvedImportWarning)
+        assert (
+            f"Could not resolve import of {expected_unresolved} in foo.one, line 1."
+            == str(warning)
+        )
+
+
+class TestDynamicImports:
+    @pytest.mark.parametrize(
+        "import_statement, call",
+        (
+            ("import importlib", 'importlib.import_module("foo.two")'),
+            ("import importlib.util", 'importlib.import_module("foo.two")'),
+            ("import importlib as il", 'il.import_module("foo.two")'),
+            ("from importlib import import_module", 'import_module("foo.two")'),
+            ("from importlib import import_module as im", 'im("foo.two")'),
+        ),
+    )
+    def test_finds_literal_import_module_calls(self, import_statement, call):
+        import_scanner = self._build_import_scanner(
+            f"""
+            {import_statement}
+            def load():
+                return {call}
+            """
+        )
+
+        result = import_scanner.scan_for_imports(Module("foo.one"))
+
+        assert result == {
+            DirectImport(
+                importer=Module("foo.one"),
+                imported=Module("foo.two"),
+                line_number=3,
+                line_contents=f"return {call}",
+                is_dynamic=True,
+                scope=ImportScope.FUNCTION,
+            )
+        }
+
+    @pytest.mark.parametrize(
+        "source",
+        (
+            # Not imported from importlib.
+            'import_module("foo.two")',
+            # Not a string literal.
+            "import importlib\nimportlib.import_module(name)",
+            # Relative module names aren't supported.
+            'import importlib\nimportlib.import_module(".two", package="foo")',
+            # A module that doesn't exist.
+            'import importlib\nimportlib.import_module("foo.three")',
+            # A different function.
+            'import importlib\nimportlib.reload("foo.two")',
+        ),
+    )
+    def test_ignores_unsupported_calls(self, source):
+        import_scanner = self._build_import_scanner(source)
+
+        result = import_scanner.scan_for_imports(Module("foo.one"))
+
+        assert result == set()
+
+    @pytest.mark.parametrize(
+        "include_external_packages, expected_result",
+        (
+            (False, set()),
+            (
+                True,
+                {
+                    DirectImport(
+                        importer=Module("foo.one"),
+                        imported=Module("importlib"),
+                        line_number=1,
+                        line_contents="import importlib",
+                    ),
+                    DirectImport(
+                        importer=Module("foo.one"),
+                        imported=Module("externalone"),
+                        line_number=2,
+                        line_contents='importlib.import_module("externalone.blue")',
+                        is_dynamic=True,
+                    )
+                },
+            ),
+        ),
+    )
+    def test_external_packages(self, include_external_packages, expected_result):
+        import_scanner = self._build_import_scanner(
+            """
+            import importlib
+            importlib.import_module("externalone.blue")
+            """,
+            include_external_packages=include_external_packages,
+        )
+
+        result = import_scanner.scan_for_imports(Module("foo.one"))
+
+        assert result == expected_result
+
+    def test_not_included_by_default(self):
+        import_scanner = self._build_import_scanner(
+            """
+            import importlib
+            importlib.import_module("foo.two")
+            """,
+            include_dynamic_imports=False,
+        )
+
+        result = import_scanner.scan_for_imports(Module("foo.one"))
+
+        assert result == set()
+
+    def _build_import_scanner(
+        self, source, include_external_packages=False, include_dynamic_imports=True
+    ):
+        all_modules = {Module("foo"), Module("foo.one"), Module("foo.two")}
+        file_system = FakeFileSystem(
+            contents="""
+                /path/to/foo/
+                    __init__.py
+                    one.py
+                    two.py
+            """,
+            content_map={"/path/to/foo/one.py": source},
+        )
+        return ImportScanner(
+            modules_by_package_directory={"/path/to/foo": all_modules},
+            file_system=file_system,
+            include_external_packages=include_external_packages,
+            include_dynamic_imports=include_dynamic_imports,
+        )
+
+
+class TestLazyImports:
+    @pytest.mark.parametrize(
+        "source",
+        (
+            """
+            import importlib
+            _submodules = ["one", "two"]
+            def __getattr__(name):
+                if name in _submodules:
+                    return importlib.import_module(f"{__name__}.{name}")
+            """,
+            """
+            from importlib import import_module
+            _submodules = ("one", "two", "three")
+            def __getattr__(name):
+                if name in _submodules:
+                    return import_module("." + name, __name__)
+            """,
+            """
+            import importlib
+            _lazy_objects = {"One": "one", "Two": "two"}
+            def __getattr__(name):
+                if name in _lazy_objects:
+                    module = importlib.import_module(f"foo.{_lazy_objects[name]}")
+                    return getattr(module, name)
+            """,
+        ),
+    )
+    def test_finds_lazy_imports(self, source):
+        import_scanner = self._build_import_scanner(source)
+
+        result = import_scanner.scan_for_imports(Module("foo"))
+
+        assert {(i.imported, i.is_lazy, i.is_dynamic, i.scope) for i in result} == {
+            (Module("foo.one"), True, True, ImportScope.FUNCTION),
+            (Module("foo.two"), True, True, ImportScope.FUNCTION),
+        }
+        assert {i.line_number for i in result} == {5}
+
+    def test_ignores_getattr_not_at_module_level(self):
+        import_scanner = self._build_import_scanner(
+            """
+            import importlib
+            _submodules = ["one", "two"]
+            class Lazy:
+                def __getattr__(self, name):
+                    if name in _submodules:
+                        return importlib.import_module(f"{__name__}.{name}")
+            """
+        )
+
+        result = import_scanner.scan_for_imports(Module("foo"))
+
+        assert result == set()
+
+    def test_respects_ignore_directives(self):
+        import_scanner = self._build_import_scanner(
+            """
+            import importlib
+            _submodules = ["one", "two"]
+            def __getattr__(name):
+                if name in _submodules:
+                    return importlib.import_module(f"{__name__}.{name}")  # grimp: ignore
+            """
+        )
+
+        result = import_scanner.scan_for_imports(Module("foo"))
+
+        assert result == set()
+
+    def test_not_included_by_default(self):
+        import_scanner = self._build_import_scanner(
+            """
+            import importlib
+            _submodules = ["one", "two"]
+            def __getattr__(name):
+                if name in _submodules:
+                    return importlib.import_module(f"{__name__}.{name}")
+            """,
+            include_dynamic_imports=False,
+        )
+
+        result = import_scanner.scan_for_imports(Module("foo"))
+
+        assert result == set()
+
+    def _build_import_scanner(self, source, include_dynamic_imports=True):
+        all_modules = {Module("foo"), Module("foo.one"), Module("foo.two")}
+        file_system = FakeFileSystem(
+            contents="""
+                /path/to/foo/
+                    __init__.py
+                    one.py
+                    two.py
+            """,
+            content_map={"/path/to/foo/__init__.py": source},
+        )
+        return ImportScanner(
+            modules_by_package_directory={"/path/to/foo": all_modules},
+            file_system=file_system,
+            include_dynamic_imports=include_dynamic_imports,
+        )
+
+
+class TestResolveWildcardImports:
+    @pytest.mark.parametrize(
+        "all_source",
+        (
+            '__all__ = ["yellow", "blue_function"]',
+            '__all__ = ("yellow", "blue_function")',
+            '__all__: list = ["yellow"]\n__all__ += ["blue_function"]',
+        ),
+    )
+    def test_expands_names_in_all(self, all_source):
+        import_scanner = self._build_import_scanner(all_source)
+
+        result = import_scanner.scan_for_imports(Module("foo.one"))
+
+        assert result == {
+            DirectImport(
+                importer=Module("foo.one"),
+                imported=Module("foo.two.yellow"),
+                line_number=1,
+                line_contents="from .two import *",
+            ),
+            DirectImport(
+                importer=Module("foo.one"),
+                imported=Module("foo.two"),
+                line_number=1,
+                line_contents="from .two import *",
+                imported_object="foo.two.blue_function",
+            ),
+        }
+
+    @pytest.mark.parametrize(
+        "all_source, resolve_wildcard_imports",
+        (
+            ('__all__ = ["yellow", "blue_function"]', False),
+            ("# No __all__.", True),
+            ("__all__ = []", True),
+            ("__all__ = get_names()", True),
+            ('__all__ = ["yellow"]\n__all__ += get_names()', True),
+        ),
+    )
+    def test_leaves_wildcard_if_unresolvable(self, all_source, resolve_wildcard_imports):
+        import_scanner = self._build_import_scanner(
+            all_source, resolve_wildcard_imports=resolve_wildcard_imports
+        )
+
+        result = import_scanner.scan_for_imports(Module("foo.one"))
+
+        assert result == {
+            DirectImport(
+                importer=Module("foo.one"),
+                imported=Module("foo.two"),
+                line_number=1,
+                line_contents="from .two import *",
+                imported_object="foo.two.*",
+            ),
+        }
+
+    def _build_import_scanner(self, all_source, resolve_wildcard_imports=True):
+        all_modules = {
+            Module("foo"),
+            Module("foo.one"),
+            Module("foo.two"),
+            Module("foo.two.yellow"),
+        }
+        file_system = FakeFileSystem(
+            contents="""
+                /path/to/foo/
+                    __init__.py
+                    one.py
+                    two/
+                        __init__.py
+                        yellow.py
+            """,
+            content_map={
+                "/path/to/foo/one.py": "from .two import *",
+                "/path/to/foo/two/__init__.py": all_source,
+            },
+        )
+        return ImportScanner(
+            modules_by_package_directory={"/path/to/foo": all_modules},
+            file_system=file_system,
+            resolve_wildcard_imports=resolve_wildcard_imports,
+            include_imported_objects=True,
+        )
+
+
+class TestResolveReexports:
+    ALL_MODULES = {
+        Module("foo"),
+        Module("foo.one"),
+        Module("foo.two"),
+        Module("foo.two.blue"),
+        Module("foo.two.green"),
+        Module("foo.two.green.alpha"),
+    }
+
+    @pytest.mark.parametrize(
+        "init_source, expected_imported, expected_object",
+        (
+            ("from .blue import Thing", "foo.two.blue", "foo.two.blue.Thing"),
+            ("from foo.two.blue import Thing", "foo.two.blue", "foo.two.blue.Thing"),
+            ("from .blue import _Thing as Thing", "foo.two.blue", "foo.two.blue._Thing"),
+            (
+                "try:\n    from .blue import Thing\nexcept ImportError:\n    Thing = None",
+                "foo.two.blue",
+                "foo.two.blue.Thing",
+            ),
+            # Chains of re-exports are followed.
+            ("from .green import Thing", "foo.two.green.alpha", "foo.two.green.alpha.Thing"),
+            # Re-exported modules are resolved to the module itself.
+            ("from .green import alpha as Thing", "foo.two.green.alpha", None),
+        ),
+    )
+    def test_resolves_reexported_object(self, init_source, expected_imported, expected_object):
+        import_scanner = self._build_import_scanner(init_source)
+
+        result = import_scanner.scan_for_imports(Module("foo.one"))
+
+        assert result == {
+            DirectImport(
+                importer=Module("foo.one"),
+                imported=Module(expected_imported),
+                line_number=1,
+                line_contents="from .two import Thing",
+                imported_object=expected_object,
+            ),
+        }
+
+    @pytest.mark.parametrize(
+        "init_source, resolve_reexports",
+        (
+            ("from .blue import Thing", False),
+            ("class Thing:\n    pass", True),
+            ("def get_thing():\n    from .blue import Thing", True),
+            ("from .nonexistent import Thing", True),
+            ("from external import Thing", True),
+            ("from .two import Thing", True),
+        ),
+    )
+    def test_leaves_import_if_not_reexported(self, init_source, resolve_reexports):
+        import_scanner = self._build_import_scanner(
+            init_source, resolve_reexports=resolve_reexports
+        )
+
+        result = import_scanner.scan_for_imports(Module("foo.one"))
+
+        assert result == {
+            DirectImport(
+                importer=Module("foo.one"),
+                imported=Module("foo.two"),
+                line_number=1,
+                line_contents="from .two import Thing",
+                imported_object="foo.two.Thing",
+            ),
+        }
+
+    def test_handles_circular_reexports(self):
+        import_scanner = self._build_import_scanner(
+            "from .blue import Thing", blue_source="from . import Thing"
+        )
+
+        result = import_scanner.scan_for_imports(Module("foo.one"))
+
+        assert result == {
+            DirectImport(
+                importer=Module("foo.one"),
+                imported=Module("foo.two"),
+                line_number=1,
+                line_contents="from .two import Thing",
+                imported_object="foo.two.Thing",
+            ),
+        }
+
+    def _build_import_scanner(self, init_source, resolve_reexports=True, blue_source=""):
+        file_system = FakeFileSystem(
+            contents="""
+                /path/to/foo/
+                    __init__.py
+                    one.py
+                    two/
+                        __init__.py
+                        blue.py
+                        green/
+                            __init__.py
+                            alpha.py
+            """,
+            content_map={
+                "/path/to/foo/one.py": "from .two import Thing",
+                "/path/to/foo/two/__init__.py": init_source,
+                "/path/to/foo/two/blue.py": blue_source or "class Thing:\n    pass",
+                "/path/to/foo/two/green/__init__.py": "from .alpha import Thing",
+            },
+        )
+        return ImportScanner(
+            modules_by_package_directory={"/path/to/foo": self.ALL_MODULES},
+            file_system=file_system,
+            resolve_reexports=resolve_reexports,
+            include_imported_objects=True,
+        )
+
+
+class TestCython:
+    ALL_MODULES = {
+        Module("foo"),
+        Module("foo.one"),
+        Module("foo.two"),
+        Module("foo.three"),
+    }
+
+    def test_scans_python_and_cython_files(self):
+        import_scanner = self._build_import_scanner(
+            {
+                "/path/to/foo/one.py": "import foo.two",
+                "/path/to/foo/one.pyx": "from . import three",
+                "/path/to/foo/one.pxd": "from foo cimport two",
+            }
+        )
+
+        result = import_scanner.scan_for_imports(Module("foo.one"))
+
+        assert result == {
+            DirectImport(
+                importer=Module("foo.one"),
+                imported=Module("foo.two"),
+                line_number=1,
+                line_contents="import foo.two",
+            ),
+            DirectImport(
+                importer=Module("foo.one"),
+                imported=Module("foo.three"),
+                line_number=1,
+                line_contents="from . import three",
+            ),
+            DirectImport(
+                importer=Module("foo.one"),
+                imported=Module("foo.two"),
+                line_number=1,
+                line_contents="from foo cimport two",
+                is_cimport=True,
+            ),
+        }
+
+    def test_ignores_cython_files_unless_include_cython(self):
+        import_scanner = self._build_import_scanner(
+            {
+                "/path/to/foo/one.py": "import foo.two",
+                "/path/to/foo/one.pyx": "import foo.three",
+            },
+            include_cython=False,
+        )
+
+        result = import_scanner.scan_for_imports(Module("foo.one"))
+
+        assert {Module("foo.two")} == {direct_import.imported for direct_import in result}
+
+    def test_scans_package_init_files(self):
+        import_scanner = self._build_import_scanner(
+            {
+                "/path/to/foo/__init__.py": "# No imports.",
+                "/path/to/foo/__init__.pxd": "from .one cimport Thing",
+            }
+        )
+
+        result = import_scanner.scan_for_imports(Module("foo"))
+
+        assert result == {
+            DirectImport(
+                importer=Module("foo"),
+                imported=Module("foo.one"),
+                line_number=1,
+                line_contents="from .one cimport Thing",
+                imported_object="foo.one.Thing",
+                is_cimport=True,
+            ),
+        }
+
+    @pytest.mark.parametrize(
+        "statement, expected_imported, expected_object",
+        (
+            ("import foo.two", "foo.two", None),
+            ("cimport foo.two", "foo.two", None),
+            ("cimport foo.two as two", "foo.two", None),
+            ("from foo.two cimport Thing, OtherThing as Other", "foo.two", "foo.two.Thing"),
+            ("from foo.two cimport (\n    Thing,\n)", "foo.two", "foo.two.Thing"),
+            ("from foo.two cimport \\\n    Thing", "foo.two", "foo.two.Thing"),
+            ("from . cimport two  # A comment.", "foo.two", None),
+        ),
+    )
+    def test_parses_statements(self, statement, expected_imported, expected_object):
+        import_scanner = self._build_import_scanner({"/path/to/foo/one.pyx": statement})
+
+        result = import_scanner.scan_for_imports(Module("foo.one"))
+
+        assert {(Module(expected_imported), expected_object)} <= {
+            (direct_import.imported, direct_import.imported_object) for direct_import in result
+        }
+        assert {statement.splitlines()[0].strip()} == {
+            direct_import.line_contents for direct_import in result
+        }
+
+    @pytest.mark.parametrize(
+        "source, expected_scope",
+        (
+            ("import foo.two", ImportScope.MODULE),
+            ("if True:\n    import foo.two", ImportScope.MODULE),
+            ("def f():\n    import foo.two", ImportScope.FUNCTION),
+            ("cdef int f(int x):\n    import foo.two", ImportScope.FUNCTION),
+            ("cpdef f():\n    import foo.two", ImportScope.FUNCTION),
+            ("cdef class A:\n    import foo.two", ImportScope.CLASS),
+            ("class A:\n    def f(self):\n        import foo.two", ImportScope.FUNCTION),
+            ('cdef extern from "foo.h":\n    int x\nimport foo.two', ImportScope.MODULE),
+            ("def f():\n    pass\nimport foo.two", ImportScope.MODULE),
+        ),
+    )
+    def test_records_scope(self, source, expected_scope):
+        import_scanner = self._build_import_scanner({"/path/to/foo/one.pyx": source})
+
+        result = import_scanner.scan_for_imports(Module("foo.one"))
+
+        assert [expected_scope] == [direct_import.scope for direct_import in result]
+
+    @pytest.mark.parametrize(
+        "source, expected_column, expected_end_line_number",
+        (
+            ("from foo cimport two", 0, 1),
+            ("def f():\n    from foo.two cimport (\n        Thing,\n    )", 4, 4),
+        ),
+    )
+    def test_records_position(self, source, expected_column, expected_end_line_number):
+        import_scanner = self._build_import_scanner({"/path/to/foo/one.pyx": source})
+
+        [direct_import] = import_scanner.scan_for_imports(Module("foo.one"))
+
+        assert direct_import.column == expected_column
+        assert direct_import.end_line_number == expected_end_line_number
+
+    def test_ignores_statements_in_docstrings(self):
+        import_scanner = self._build_import_scanner(
+            {
+                "/path/to/foo/one.pyx": '''
+                    """
+                    import foo.three
+                    """
+                    import foo.two
+                '''
+            }
+        )
+
+        result = import_scanner.scan_for_imports(Module("foo.one"))
+
+        assert {Module("foo.two")} == {direct_import.imported for direct_import in result}
+
+    def _build_import_scanner(self, content_map, include_cython=True):
+        file_system = FakeFileSystem(content_map=content_map)
+        return ImportScanner(
+            modules_by_package_directory={"/path/to/foo": self.ALL_MODULES},
+            file_system=file_system,
+            include_cython=include_cython,
+            include_imported_objects=True,
+        )
@@ -0,0 +1,80 @@
+import os
+import time
+from copy import copy
+
+import pytest  # type: ignore
+
+from grimp.adaptors.memoryfilesystem import InMemoryFileSystem
+
+
+class TestInMemoryFileSystem:
+    def test_walk(self):
+        file_system = self._build_file_system()
+        package_directory = os.path.join(os.sep, "mypackage")
+
+        assert [
+            (package_directory, ["foo"], ["__init__.py"]),
+            (os.path.join(package_directory, "foo"), ["skipme"], ["__init__.py", "one.py"]),
+            (os.path.join(package_directory, "foo", "skipme"), [], ["two.py"]),
+        ] == list(file_system.walk(package_directory))
+
+    def test_dirnames_can_be_modified_in_place(self):
+        file_system = self._build_file_system()
+        package_directory = os.path.join(os.sep, "mypackage")
+
+        actual_tuples = []
+        for dirpath, dirnames, filenames in file_system.walk(package_directory):
+            actual_tuples.append((dirpath, copy(dirnames), filenames))
+            if "skipme" in dirnames:
+                dirnames.remove("skipme")
+
+        assert [
+            (package_directory, ["foo"], ["__init__.py"]),
+            (os.path.join(package_directory, "foo"), ["skipme"], ["__init__.py", "one.py"]),
+        ] == actual_tuples
+
+    def test_walk_of_missing_directory_yields_nothing(self):
+        file_system = self._build_file_system()
+
+        assert [] == list(file_system.walk(os.path.join(os.sep, "missing")))
+
+    def test_read(self):
+        file_system = self._build_file_system()
+
+        assert "import mypackage" == file_system.read(
+            os.path.join(os.sep, "mypackage", "foo", "one.py")
+        )
+        with pytest.raises(FileNotFoundError):
+            file_system.read(os.path.join(os.sep, "mypackage", "missing.py"))
+
+    def test_exists(self):
+        file_system = self._build_file_system()
+
+        assert file_system.exists(os.path.join(os.sep, "mypackage", "foo", "__init__.py"))
+        assert not file_system.exists(os.path.join(os.sep, "mypackage", "foo"))
+        assert not file_system.exists(os.path.join(os.sep, "mypackage", "missing.py"))
+
+    def test_getsize(self):
+        file_system = self._build_file_system()
+
+        assert 16 == file_system.getsize(os.path.join(os.sep, "mypackage", "foo", "one.py"))
+
+    def test_getmtime_is_time_file_was_added(self):
+        before = time.time()
+        file_system = self._build_file_system()
+        after = time.time()
+
+        assert before <= file_system.getmtime(os.path.join(os.sep, "mypackage", "__init__.py"))
+        assert after >= file_system.getmtime(os.path.join(os.sep, "mypackage", "__init__.py"))
+        with pytest.raises(FileNotFoundError):
+            file_system.getmtime(os.path.join(os.sep, "mypackage", "missing.py"))
+
+    def _build_file_system(self):
+        return InMemoryFileSystem(
+            {
+                os.path.join(os.sep, "mypackage", "__init__.py"): "",
+                os.path.join(os.sep, "mypackage", "foo", "__init__.py"): "",
+                os.path.join(os.sep, "mypackage", "foo", "one.py"): "import mypackage",
+                os.path.join(os.sep, "mypackage", "foo", "skipme", "two.py"): "",
+            }
+        )
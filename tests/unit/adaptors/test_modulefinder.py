@@ -1,3 +1,9 @@
+import os
+import sys
+
+import pytest  # type: ignore
+from grimp import exceptions
+from grimp.adaptors.filesystem import FileSystem
 from grimp.adaptors.modulefinder import ModuleFinder
 from grimp.domain.valueobjects import Module
 
@@ -41,6 +47,32 @@ def test_happy_path():
     assert set(result) == expected_modules
 
 
+def test_names_modules_after_package_rather_than_directory():
+    module_finder = ModuleFinder()
+
+    file_system = FakeFileSystem(
+        contents="""
+        /path/to/lib/
+            __init__.py
+            foo/
+                __init__.py
+                one.py
+        """
+    )
+
+    result = module_finder.find_modules(
+        package_name="mypackage",
+        package_directory="/path/to/lib",
+        file_system=file_system,
+    )
+
+    assert {
+        Module("mypackage"),
+        Module("mypackage.foo"),
+        Module("mypackage.foo.one"),
+    } == set(result)
+
+
 def test_ignores_orphaned_python_files():
     # Python files in directories that don't contain an __init__.py should not be discovered.
     module_finder = ModuleFinder()
@@ -74,6 +106,163 @@ def test_ignores_orphaned_python_files():
     assert set(result) == expected_modules
 
 
+def test_includes_namespace_portions_if_namespace_packages():
+    module_finder = ModuleFinder()
+
+    file_system = FakeFileSystem(
+        contents="""
+            /path/to/mypackage/
+                __init__.py
+                two/
+                    __init__.py
+                    green.py
+                noinitpackage/
+                    green.py
+                    orphan/
+                        __init__.py
+                        red.py
+                    .hidden/
+                        blue.py
+            """
+    )
+
+    result = module_finder.find_modules(
+        package_name="mypackage",
+        package_directory="/path/to/mypackage",
+        file_system=file_system,
+        namespace_packages=True,
+    )
+
+    expected_modules = {
+        Module("mypackage"),
+        Module("mypackage.two"),
+        Module("mypackage.two.green"),
+        Module("mypackage.noinitpackage.green"),
+        Module("mypackage.noinitpackage.orphan"),
+        Module("mypackage.noinitpackage.orphan.red"),
+    }
+    assert set(result) == expected_modules
+
+
+def test_respects_gitignore_files():
+    module_finder = ModuleFinder()
+
+    file_system = FakeFileSystem(
+        contents="""
+            /path/to/mypackage/
+                __init__.py
+                .gitignore
+                generated.py
+                build/
+                    __init__.py
+                    green.py
+                two/
+                    __init__.py
+                    green.py
+                    generated.py
+                    venv/
+                        __init__.py
+                        blue.py
+            """,
+        content_map={
+            "/path/to/.gitignore": """
+                build/
+                venv/
+            """,
+            "/path/to/mypackage/.gitignore": "/generated.py",
+        },
+    )
+
+    result = module_finder.find_modules(
+        package_name="mypackage",
+        package_directory="/path/to/mypackage",
+        file_system=file_system,
+        respect_gitignore=True,
+    )
+
+    expected_modules = {
+        Module("mypackage"),
+        Module("mypackage.two"),
+        Module("mypackage.two.green"),
+        Module("mypackage.two.generated"),
+    }
+    assert set(result) == expected_modules
+    assert sorted(result.ignored_files, key=lambda f: f["filename"]) == [
+        {"filename": filename, "reason": "Ignored by .gitignore."}
+        for filename in (
+            "/path/to/mypackage/build",
+            "/path/to/mypackage/generated.py",
+            "/path/to/mypackage/two/venv",
+        )
+    ]
+
+
+@pytest.mark.parametrize(
+    "git_filename, git_file_contents",
+    (
+        ("/path/to/repository/.git/HEAD", "ref: refs/heads/main"),
+        # In a worktree or submodule, .git is a file.
+        ("/path/to/repository/.git", "gitdir: /path/to/main/.git/worktrees/repository"),
+    ),
+)
+def test_ignores_gitignore_files_outside_repository(git_filename, git_file_contents):
+    module_finder = ModuleFinder()
+
+    file_system = FakeFileSystem(
+        contents="""
+            /path/to/repository/mypackage/
+                __init__.py
+                one.py
+                two.py
+            """,
+        content_map={
+            git_filename: git_file_contents,
+            "/path/to/.gitignore": "one.py",
+            "/path/to/repository/.gitignore": "two.py",
+        },
+    )
+
+    result = module_finder.find_modules(
+        package_name="mypackage",
+        package_directory="/path/to/repository/mypackage",
+        file_system=file_system,
+        respect_gitignore=True,
+    )
+
+    assert {Module("mypackage"), Module("mypackage.one")} == set(result)
+
+
+@pytest.mark.parametrize("include_cython", (True, False))
+def test_includes_cython_files_if_include_cython(include_cython):
+    module_finder = ModuleFinder()
+
+    file_system = FakeFileSystem(
+        contents="""
+            /path/to/mypackage/
+                __init__.py
+                __init__.pxd
+                one.py
+                two.pyx
+                two.pxd
+                three.pxd
+                four.pyi
+                five.c
+        """
+    )
+
+    result = module_finder.find_modules(
+        package_name="mypackage",
+        package_directory="/path/to/mypackage",
+        file_system=file_system,
+        include_cython=include_cython,
+    )
+
+    expected_modules = [Module("mypackage"), Module("mypackage.one")]
+    if include_cython:
+        expected_modules += [Module("mypackage.two"), Module("mypackage.three")]
+    assert sorted(result, key=str) == sorted(expected_modules, key=str)
+
+
 def test_ignores_hidden_directories():
     module_finder = ModuleFinder()
 
@@ -104,3 +293,348 @@ def test_ignores_hidden_directories():
         Module("mypackage.two.green"),
     }
     assert set(result) == expected_modules
+
+
+def test_ignores_dotted_filenames_with_warnings():
+    module_finder = ModuleFinder()
+
+    file_system = FakeFileSystem(
+        contents="""
+                /path/to/mypackage/
+                    __init__.py
+                    one.py
+                    one.backup.py
+                    two.0/
+                        __init__.py
+                        green.py
+                    data.d/
+                        notes.txt
+                """
+    )
+
+    result = module_finder.find_modules(
+        package_name="mypackage",
+        package_directory="/path/to/mypackage",
+        file_system=file_system,
+    )
+
+    assert set(result) == {Module("mypackage"), Module("mypackage.one")}
+    assert [
+        "Ignored /path/to/mypackage/two.0, as its name contains a dot so it can't be "
+        "imported as a module.",
+        "Ignored /path/to/mypackage/one.backup.py, as its name contains a dot so it can't be "
+        "imported as a module.",
+    ] == [str(warning) for warning in result.warnings]
+    assert all(
+        isinstance(warning, exceptions.DottedFilenameWarning)
+        for warning in result.warnings
+    )
+
+
+def test_records_ignored_files():
+    module_finder = ModuleFinder()
+
+    file_system = FakeFileSystem(
+        contents="""
+                /path/to/mypackage/
+                    __init__.py
+                    one.py
+                    .two.py
+                    one.backup.py
+                    .hidden/
+                        __init__.py
+                    .git/
+                        config
+                    scripts/
+                        run.py
+                    templates/
+                        base.html
+                """
+    )
+
+    result = module_finder.find_modules(
+        package_name="mypackage",
+        package_directory="/path/to/mypackage",
+        file_system=file_system,
+    )
+
+    assert set(result) == {Module("mypackage"), Module("mypackage.one")}
+    assert sorted(result.ignored_files, key=lambda f: f["filename"]) == [
+        {"filename": "/path/to/mypackage/.hidden", "reason": "Hidden directory."},
+        {"filename": "/path/to/mypackage/.two.py", "reason": "Hidden file."},
+        {
+            "filename": "/path/to/mypackage/one.backup.py",
+            "reason": "Name contains a dot, so it can't be imported as a module.",
+        },
+        {
+            "filename": "/path/to/mypackage/scripts",
+            "reason": "Directory has no __init__.py file, so is not a package.",
+        },
+    ]
+
+
+@pytest.mark.parametrize("generated_file_marker", (None, "# @generated"))
+def test_ignores_generated_files_if_generated_file_marker(generated_file_marker):
+    module_finder = ModuleFinder()
+
+    file_system = FakeFileSystem(
+        contents="""
+                /path/to/mypackage/
+                    __init__.py
+                    one.py
+                    one_pb2.py
+                    models.py
+                """,
+        content_map={
+            "/path/to/mypackage/one_pb2.py": """
+                # -*- coding: utf-8 -*-
+                # @generated by the protocol buffer compiler.
+                import sys
+            """,
+            # The marker is too far down the file to count.
+            "/path/to/mypackage/models.py": "\n".join(["import os"] * 10 + ["# @generated"]),
+        },
+    )
+
+    result = module_finder.find_modules(
+        package_name="mypackage",
+        package_directory="/path/to/mypackage",
+        file_system=file_system,
+        generated_file_marker=generated_file_marker,
+    )
+
+    expected_modules = {Module("mypackage"), Module("mypackage.one"), Module("mypackage.models")}
+    if generated_file_marker:
+        assert set(result) == expected_modules
+        assert result.ignored_files == [
+            {
+                "filename": "/path/to/mypackage/one_pb2.py",
+                "reason": "Generated file, as it contains '# @generated' in its first 10 lines.",
+            }
+        ]
+    else:
+        assert set(result) == expected_modules | {Module("mypackage.one_pb2")}
+        assert result.ignored_files == []
+
+
+def test_includes_files_with_additional_module_extensions():
+    module_finder = ModuleFinder()
+
+    file_system = FakeFileSystem(
+        contents="""
+                /path/to/mypackage/
+                    __init__.py
+                    one.py
+                    two.pyw
+                    three.py.in
+                    four.in
+                    five.backup.pyw
+                """
+    )
+
+    result = module_finder.find_modules(
+        package_name="mypackage",
+        package_directory="/path/to/mypackage",
+        file_system=file_system,
+        additional_module_extensions=(".pyw", ".py.in"),
+    )
+
+    assert set(result) == {
+        Module("mypackage"),
+        Module("mypackage.one"),
+        Module("mypackage.two"),
+        Module("mypackage.three"),
+    }
+    assert result.ignored_files == [
+        {
+            "filename": "/path/to/mypackage/five.backup.pyw",
+            "reason": "Name contains a dot, so it can't be imported as a module.",
+        }
+    ]
+
+
+@pytest.mark.parametrize(
+    "skipped_directory_names, expected_extra_modules, expected_ignored_directories",
+    (
+        (None, set(), ["/path/to/mypackage/__pycache__", "/path/to/mypackage/build"]),
+        (["generated"], {Module("mypackage.build.one")}, []),
+    ),
+)
+def test_skips_build_directories_and_virtual_environments(
+    skipped_directory_names, expected_extra_modules, expected_ignored_directories
+):
+    module_finder = ModuleFinder()
+
+    file_system = FakeFileSystem(
+        contents="""
+                /path/to/mypackage/
+                    __init__.py
+                    one.py
+                    build/
+                        one.py
+                    __pycache__/
+                        one.cpython-38.pyc
+                    myenv/
+                        pyvenv.cfg
+                        lib/
+                            __init__.py
+                """
+    )
+
+    result = module_finder.find_modules(
+        package_name="mypackage",
+        package_directory="/path/to/mypackage",
+        file_system=file_system,
+        namespace_packages=True,
+        skipped_directory_names=skipped_directory_names,
+    )
+
+    assert set(result) == {Module("mypackage"), Module("mypackage.one")} | expected_extra_modules
+    assert sorted(result.ignored_files, key=lambda f: f["filename"]) == [
+        {"filename": directory, "reason": "Directory is in the skipped directory names."}
+        for directory in expected_ignored_directories
+    ] + [{"filename": "/path/to/mypackage/myenv", "reason": "Virtual environment."}]
+
+
+def test_includes_packages_with_skipped_directory_names():
+    module_finder = ModuleFinder()
+
+    file_system = FakeFileSystem(
+        contents="""
+                /path/to/mypackage/
+                    __init__.py
+                    build/
+                        __init__.py
+                        one.py
+                    dist/
+                        one.py
+                """
+    )
+
+    result = module_finder.find_modules(
+        package_name="mypackage",
+        package_directory="/path/to/mypackage",
+        file_system=file_system,
+    )
+
+    assert set(result) == {
+        Module("mypackage"),
+        Module("mypackage.build"),
+        Module("mypackage.build.one"),
+    }
+    assert result.ignored_files == [
+        {
+            "filename": "/path/to/mypackage/dist",
+            "reason": "Directory has no __init__.py file, so is not a package.",
+        }
+    ]
+
+
+@pytest.mark.skipif(sys.platform == "win32", reason="Creating symlinks requires privileges.")
+class TestFollowSymlinks:
+    def _make_package(self, root):
+        """
+        Create a package on disk, in the form:
+
+            mypackage/
+                __init__.py
+                one.py
+                linked -> ../elsewhere/linked
+                linkedagain -> ../elsewhere/linked
+            elsewhere/
+                linked/
+                    __init__.py
+                    green.py
+                    cycle -> ../../mypackage
+        """
+        package_directory = os.path.join(root, "mypackage")
+        linked_directory = os.path.join(root, "elsewhere", "linked")
+        os.makedirs(package_directory)
+        os.makedirs(linked_directory)
+        for filename in (
+            os.path.join(package_directory, "__init__.py"),
+            os.path.join(package_directory, "one.py"),
+            os.path.join(linked_directory, "__init__.py"),
+            os.path.join(linked_directory, "green.py"),
+        ):
+            open(filename, "w").close()
+        os.symlink(linked_directory, os.path.join(package_directory, "linked"))
+        os.symlink(linked_directory, os.path.join(package_directory, "linkedagain"))
+        os.symlink(package_directory, os.path.join(linked_directory, "cycle"))
+        return package_directory
+
+    def test_does_not_follow_symlinks_by_default(self, tmp_path):
+        package_directory = self._make_package(str(tmp_path))
+
+        result = ModuleFinder().find_modules(
+            package_name="mypackage",
+            package_directory=package_directory,
+            file_system=FileSystem(),
+        )
+
+        assert set(result) == {Module("mypackage"), Module("mypackage.one")}
+
+    def test_follows_symlinks_once_each(self, tmp_path):
+        package_directory = self._make_package(str(tmp_path))
+
+        result = list(
+            ModuleFinder().find_modules(
+                package_name="mypackage",
+                package_directory=package_directory,
+                file_system=FileSystem(),
+                follow_symlinks=True,
+            )
+        )
+
+        # The linked directory is reachable via two links; it should only be included once,
+        # under whichever link was walked first. The cycle back to the package is not followed.
+        linked_names = {m.name.split(".")[1] for m in result if len(m.name.split(".")) > 2}
+        assert len(linked_names) == 1
+        (link_name,) = linked_names
+        assert set(result) == {
+            Module("mypackage"),
+            Module("mypackage.one"),
+            Module(f"mypackage.{link_name}"),
+            Module(f"mypackage.{link_name}.green"),
+        }
+        assert len(result) == 4
+
+
+def test_results_are_not_shared_between_searches():
+    file_system = FakeFileSystem(
+        contents="""
+                /path/to/mypackage/
+                    __init__.py
+                    one.py
+                    two/
+                        green.py
+                /path/to/otherpackage/
+                    __init__.py
+                """
+    )
+    module_finder = ModuleFinder()
+
+    result = module_finder.find_modules(
+        package_name="mypackage", package_directory="/path/to/mypackage", file_system=file_system
+    )
+    other_result = module_finder.find_modules(
+        package_name="otherpackage",
+        package_directory="/path/to/otherpackage",
+        file_system=file_system,
+    )
+
+    assert result.ignored_files == [
+        {
+            "filename": "/path/to/mypackage/two",
+            "reason": "Directory has no __init__.py file, so is not a package.",
+        }
+    ]
+    assert result.module_filenames == {
+        Module("mypackage"): "/path/to/mypackage/__init__.py",
+        Module("mypackage.one"): "/path/to/mypackage/one.py",
+    }
+    assert other_result.warnings == []
+    assert other_result.ignored_files == []
+    assert other_result.module_filenames == {
+        Module("otherpackage"): "/path/to/otherpackage/__init__.py"
+    }
@@ -1,5 +1,12 @@
+import warnings
+from unittest import mock
+
 import pytest  # type: ignore
-from grimp.application import usecases
+from grimp import __version__, exceptions
+from grimp.adaptors.filesystem import FileSystem
+from grimp.application import projectconfig, usecases
+from grimp.application.ports.modulefinder import AbstractModuleFinder, FoundModules
+from grimp.domain.valueobjects import Module
 
 from tests.adaptors.filesystem import FakeFileSystem
 from tests.adaptors.packagefinder import BaseFakePackageFinder
@@ -56,6 +63,13 @@ class TestBuildGraph:
         for importer, imported_modules in expected_import_map.items():
             assert graph.find_modules_directly_imported_by(importer) == imported_modules
 
+        # Check that the external packages are classified.
+        if include_external_packages:
+            assert {"decimal"} == graph.find_external_modules(kind="stdlib")
+            assert {"external"} == graph.find_external_modules(kind="third_party")
+        else:
+            assert set() == graph.find_external_modules()
+
         # Check that the external packages are squashed modules.
         if include_external_packages:
             for module in ("external", "decimal"):
@@ -64,6 +78,656 @@ class TestBuildGraph:
                 ):
                     graph.find_children(module)
 
+    @pytest.mark.parametrize(
+        "external_import_granularity, expected_external_modules",
+        (
+            ("root", {"django", "os"}),
+            ("full", {"django", "django.db.models", "os.path"}),
+        ),
+    )
+    def test_external_import_granularity(
+        self, external_import_granularity, expected_external_modules
+    ):
+        file_system = FakeFileSystem(
+            contents="""
+                /path/to/mypackage/
+                    __init__.py
+                    one.py
+            """,
+            content_map={
+                "/path/to/mypackage/one.py": "import os.path\n"
+                "import django\n"
+                "from django.db.models import JSONField",
+            },
+        )
+
+        class FakePackageFinder(BaseFakePackageFinder):
+            directory_map = {"mypackage": "/path/to/mypackage"}
+
+        with override_settings(FILE_SYSTEM=file_system, PACKAGE_FINDER=FakePackageFinder()):
+            graph = usecases.build_graph(
+                "mypackage",
+                include_external_packages=True,
+                external_import_granularity=external_import_granularity,
+            )
+
+        assert graph.find_external_modules() == expected_external_modules
+        assert graph.find_modules_directly_imported_by("mypackage.one") == (
+            expected_external_modules
+        )
+        assert graph.build_info()["external_import_granularity"] == external_import_granularity
+
+    def test_unknown_external_import_granularity_raises_value_error(self):
+        with pytest.raises(ValueError, match="Unknown external import granularity partial."):
+            usecases.build_graph("mypackage", external_import_granularity="partial")
+
+    def test_additional_module_extensions(self):
+        file_system = FakeFileSystem(
+            contents="""
+                /path/to/mypackage/
+                    __init__.py
+                    one.py
+                    two.pyw
+            """,
+            content_map={
+                "/path/to/mypackage/two.pyw": "from . import one",
+            },
+        )
+
+        class FakePackageFinder(BaseFakePackageFinder):
+            directory_map = {"mypackage": "/path/to/mypackage"}
+
+        with override_settings(FILE_SYSTEM=file_system, PACKAGE_FINDER=FakePackageFinder()):
+            graph = usecases.build_graph("mypackage", additional_module_extensions=[".pyw"])
+
+        assert {"mypackage", "mypackage.one", "mypackage.two"} == graph.modules
+        assert graph.direct_import_exists(importer="mypackage.two", imported="mypackage.one")
+        assert graph.build_info()["additional_module_extensions"] == [".pyw"]
+
+    def test_additional_module_extension_without_dot_raises_value_error(self):
+        with pytest.raises(ValueError, match="Module extension pyw should start with a dot."):
+            usecases.build_graph("mypackage", additional_module_extensions=["pyw"])
+
+    def test_records_module_provenance(self):
+        file_system = FakeFileSystem(
+            contents="""
+                /path/to/mypackage/
+                    __init__.py
+                    one.py
+            """,
+            content_map={"/path/to/mypackage/one.py": "import decimal"},
+            mtime_map={"/path/to/mypackage/one.py": 1611068672.1},
+        )
+
+        class FakePackageFinder(BaseFakePackageFinder):
+            directory_map = {"mypackage": "/path/to/mypackage"}
+
+        with override_settings(FILE_SYSTEM=file_system, PACKAGE_FINDER=FakePackageFinder()):
+            graph = usecases.build_graph("mypackage", include_external_packages=True)
+
+        assert {
+            "package_name": "mypackage",
+            "filename": "/path/to/mypackage/one.py",
+            "mtime": 1611068672.1,
+        } == graph.get_module_provenance("mypackage.one")
+        assert {
+            "package_name": "mypackage",
+            "filename": "/path/to/mypackage/__init__.py",
+            "mtime": 0.0,
+        } == graph.get_module_provenance("mypackage")
+        assert graph.get_module_provenance("decimal") is None
+
+    def test_builds_graph_with_module_finder_that_doesnt_record_filenames(self):
+        file_system = FakeFileSystem(
+            contents="""
+                /path/to/mypackage/
+                    __init__.py
+                    one.py
+                    two.py
+            """,
+            content_map={"/path/to/mypackage/one.py": "from . import two"},
+        )
+
+        class FakePackageFinder(BaseFakePackageFinder):
+            directory_map = {"mypackage": "/path/to/mypackage"}
+
+        class FakeModuleFinder(AbstractModuleFinder):
+            def find_modules(self, package_name, *args, **kwargs):
+                return [Module("mypackage"), Module("mypackage.one"), Module("mypackage.two")]
+
+        with override_settings(
+            FILE_SYSTEM=file_system,
+            PACKAGE_FINDER=FakePackageFinder(),
+            MODULE_FINDER=FakeModuleFinder(),
+        ):
+            graph = usecases.build_graph("mypackage")
+
+        assert graph.direct_import_exists(importer="mypackage.one", imported="mypackage.two")
+        assert graph.get_module_provenance("mypackage.one") is None
+
+    def test_reports_ignored_files_returned_by_module_finder(self):
+        file_system = FakeFileSystem(
+            contents="""
+                /path/to/mypackage/
+                    __init__.py
+                    one.py
+            """,
+        )
+
+        class FakePackageFinder(BaseFakePackageFinder):
+            directory_map = {"mypackage": "/path/to/mypackage"}
+
+        class FakeModuleFinder(AbstractModuleFinder):
+            def find_modules(self, package_name, *args, **kwargs):
+                return FoundModules(
+                    [Module("mypackage"), Module("mypackage.one")],
+                    warnings=[exceptions.DottedFilenameWarning("Ignored one.old.py.")],
+                    ignored_files=[{"filename": "/path/to/mypackage/two", "reason": "Ignored."}],
+                )
+
+        with override_settings(
+            FILE_SYSTEM=file_system,
+            PACKAGE_FINDER=FakePackageFinder(),
+            MODULE_FINDER=FakeModuleFinder(),
+        ):
+            graph = usecases.build_graph("mypackage")
+
+        assert [
+            {"filename": "/path/to/mypackage/two", "reason": "Ignored."}
+        ] == graph.build_info()["ignored_files"]
+        assert [
+            {"category": "DottedFilenameWarning", "message": "Ignored one.old.py."}
+        ] == graph.build_info()["warnings"]
+
+    def test_namespace_packages(self):
+        file_system = FakeFileSystem(
+            contents="""
+                /path/to/mypackage/
+                    __init__.py
+                    foo/
+                        one.py
+                        two.py
+            """,
+            content_map={
+                "/path/to/mypackage/foo/one.py": "from . import two",
+            },
+        )
+
+        class FakePackageFinder(BaseFakePackageFinder):
+            directory_map = {"mypackage": "/path/to/mypackage"}
+
+        with override_settings(
+            FILE_SYSTEM=file_system, PACKAGE_FINDER=FakePackageFinder()
+        ):
+            graph = usecases.build_graph("mypackage", namespace_packages=True)
+
+        assert {"mypackage", "mypackage.foo.one", "mypackage.foo.two"} == graph.modules
+        assert graph.direct_import_exists(
+            importer="mypackage.foo.one", imported="mypackage.foo.two"
+        )
+        assert graph.build_info()["namespace_packages"] is True
+
+    def test_namespace_package_portions_in_multiple_directories(self):
+        file_system = FakeFileSystem(
+            contents="""
+                /path/to/one/mypackage/
+                    foo/
+                        __init__.py
+                        one.py
+                /path/to/two/mypackage/
+                    bar/
+                        __init__.py
+                        two.py
+            """,
+            content_map={
+                "/path/to/one/mypackage/foo/one.py": "from mypackage.bar import two",
+            },
+        )
+
+        class FakePackageFinder(BaseFakePackageFinder):
+            directory_map = {
+                "mypackage": ["/path/to/one/mypackage", "/path/to/two/mypackage"]
+            }
+
+        with override_settings(
+            FILE_SYSTEM=file_system, PACKAGE_FINDER=FakePackageFinder()
+        ):
+            graph = usecases.build_graph("mypackage", namespace_packages=True)
+
+        assert {
+            "mypackage.foo",
+            "mypackage.foo.one",
+            "mypackage.bar",
+            "mypackage.bar.two",
+        } <= graph.modules
+        assert graph.direct_import_exists(
+            importer="mypackage.foo.one", imported="mypackage.bar.two"
+        )
+
+    def test_raises_if_module_is_in_more_than_one_portion(self):
+        file_system = FakeFileSystem(
+            contents="""
+                /path/to/one/mypackage/
+                    foo/
+                        __init__.py
+                        one.py
+                /path/to/two/mypackage/
+                    foo/
+                        __init__.py
+                        two.py
+            """,
+        )
+
+        class FakePackageFinder(BaseFakePackageFinder):
+            directory_map = {
+                "mypackage": ["/path/to/one/mypackage", "/path/to/two/mypackage"]
+            }
+
+        with override_settings(
+            FILE_SYSTEM=file_system, PACKAGE_FINDER=FakePackageFinder()
+        ):
+            with pytest.raises(exceptions.DuplicateModuleFound) as excinfo:
+                usecases.build_graph("mypackage", namespace_packages=True)
+
+        assert excinfo.value == exceptions.DuplicateModuleFound(
+            module="mypackage.foo",
+            filenames=[
+                "/path/to/one/mypackage/foo/__init__.py",
+                "/path/to/two/mypackage/foo/__init__.py",
+            ],
+        )
+
+    def test_reports_filenames_of_duplicate_modules_with_additional_extensions(self):
+        file_system = FakeFileSystem(
+            contents="""
+                /path/to/one/mypackage/
+                    foo.pyw
+                /path/to/two/mypackage/
+                    foo.pyw
+            """,
+        )
+
+        class FakePackageFinder(BaseFakePackageFinder):
+            directory_map = {
+                "mypackage": ["/path/to/one/mypackage", "/path/to/two/mypackage"]
+            }
+
+        with override_settings(
+            FILE_SYSTEM=file_system, PACKAGE_FINDER=FakePackageFinder()
+        ):
+            with pytest.raises(exceptions.DuplicateModuleFound) as excinfo:
+                usecases.build_graph(
+                    "mypackage", namespace_packages=True, additional_module_extensions=[".pyw"]
+                )
+
+        assert excinfo.value == exceptions.DuplicateModuleFound(
+            module="mypackage.foo",
+            filenames=["/path/to/one/mypackage/foo.pyw", "/path/to/two/mypackage/foo.pyw"],
+        )
+
+    def test_raises_if_package_is_included_more_than_once(self):
+        file_system = FakeFileSystem(
+            contents="""
+                /path/to/mypackage/
+                    __init__.py
+                    foo.py
+            """,
+        )
+
+        class FakePackageFinder(BaseFakePackageFinder):
+            directory_map = {"mypackage": "/path/to/mypackage"}
+
+        with override_settings(
+            FILE_SYSTEM=file_system, PACKAGE_FINDER=FakePackageFinder()
+        ):
+            with pytest.raises(exceptions.DuplicateModuleFound) as excinfo:
+                usecases.build_graph("mypackage", "mypackage")
+
+        assert excinfo.value == exceptions.DuplicateModuleFound(
+            module="mypackage",
+            filenames=["/path/to/mypackage/__init__.py", "/path/to/mypackage/__init__.py"],
+        )
+
+    def test_reads_packages_from_package_directories(self):
+        file_system = FakeFileSystem(
+            contents="""
+                /path/to/project/src/mypackage/
+                    __init__.py
+                    one.py
+                    two.py
+            """,
+            content_map={
+                "/path/to/project/src/mypackage/one.py": "from . import two",
+            },
+        )
+
+        class FakePackageFinder(BaseFakePackageFinder):
+            # The package isn't on the Python path.
+            directory_map = {}
+
+        with override_settings(
+            FILE_SYSTEM=file_system, PACKAGE_FINDER=FakePackageFinder()
+        ):
+            graph = usecases.build_graph(
+                "mypackage",
+                package_directories={"mypackage": "/path/to/project/src/mypackage"},
+            )
+
+        assert {"mypackage", "mypackage.one", "mypackage.two"} == graph.modules
+        assert graph.direct_import_exists(importer="mypackage.one", imported="mypackage.two")
+        assert graph.build_info()["package_directories"] == {
+            "mypackage": "/path/to/project/src/mypackage"
+        }
+
+    def test_reads_namespace_package_portions_from_package_directories(self):
+        file_system = FakeFileSystem(
+            contents="""
+                /path/to/one/mypackage/
+                    foo/
+                        __init__.py
+                        one.py
+                /path/to/two/mypackage/
+                    bar/
+                        __init__.py
+                        two.py
+            """,
+            content_map={
+                "/path/to/one/mypackage/foo/one.py": "from mypackage.bar import two",
+            },
+        )
+
+        class FakePackageFinder(BaseFakePackageFinder):
+            # The package isn't on the Python path.
+            directory_map = {}
+
+        with override_settings(
+            FILE_SYSTEM=file_system, PACKAGE_FINDER=FakePackageFinder()
+        ):
+            graph = usecases.build_graph(
+                "mypackage",
+                namespace_packages=True,
+                package_directories={
+                    "mypackage": ("/path/to/one/mypackage", "/path/to/two/mypackage")
+                },
+            )
+
+        assert {
+            "mypackage.foo",
+            "mypackage.foo.one",
+            "mypackage.bar",
+            "mypackage.bar.two",
+        } == graph.modules
+        assert graph.direct_import_exists(
+            importer="mypackage.foo.one", imported="mypackage.bar.two"
+        )
+        assert graph.build_info()["package_directories"] == {
+            "mypackage": ["/path/to/one/mypackage", "/path/to/two/mypackage"]
+        }
+
+    def test_reports_first_syntax_error_by_module_name(self):
+        file_system = FakeFileSystem(
+            contents="""
+                /path/to/mypackage/
+                    __init__.py
+                    zulu.py
+                    alpha/
+                        __init__.py
+                        two.py
+                        one.py
+            """,
+            content_map={
+                "/path/to/mypackage/zulu.py": "fromb . import alpha",
+                "/path/to/mypackage/alpha/two.py": "fromb . import one",
+                "/path/to/mypackage/alpha/one.py": "fromb . import two",
+            },
+        )
+
+        class FakePackageFinder(BaseFakePackageFinder):
+            directory_map = {"mypackage": "/path/to/mypackage"}
+
+        with override_settings(
+            FILE_SYSTEM=file_system, PACKAGE_FINDER=FakePackageFinder()
+        ):
+            with pytest.raises(exceptions.SourceSyntaxError) as excinfo:
+                usecases.build_graph("mypackage")
+
+        assert excinfo.value.filename == "/path/to/mypackage/alpha/one.py"
+
+    def test_skips_files_larger_than_max_file_size(self):
+        file_system = FakeFileSystem(
+            contents="""
+                /path/to/mypackage/
+                    __init__.py
+                    small.py
+                    large.py
+            """,
+            content_map={
+                "/path/to/mypackage/small.py": "from . import large",
+                "/path/to/mypackage/large.py": "from . import small\nfrom . import small",
+            },
+        )
+
+        class FakePackageFinder(BaseFakePackageFinder):
+            directory_map = {"mypackage": "/path/to/mypackage"}
+
+        with override_settings(
+            FILE_SYSTEM=file_system, PACKAGE_FINDER=FakePackageFinder()
+        ):
+            graph = usecases.build_graph("mypackage", max_file_size=30)
+
+        assert {"mypackage", "mypackage.small", "mypackage.large"} == graph.modules
+        assert graph.direct_import_exists(
+            importer="mypackage.small", imported="mypackage.large"
+        )
+        assert not graph.direct_import_exists(
+            importer="mypackage.large", imported="mypackage.small"
+        )
+        assert [
+            {
+                "filename": "/path/to/mypackage/large.py",
+                "reason": "/path/to/mypackage/large.py is 39 bytes, which is larger than the "
+                "maximum of 30 bytes.",
+            }
+        ] == graph.build_info()["skipped_files"]
+
+    @pytest.mark.parametrize(
+        "contents",
+        (
+            b"# -*- coding: bogus -*-\nfrom . import good\n",
+            b"# -*- coding: utf-8 -*-\nfrom . import good  # \xff\n",
+        ),
+    )
+    def test_skips_undecodable_files_if_skip_undecodable_files(self, tmp_path, contents):
+        package_directory = tmp_path / "mypackage"
+        package_directory.mkdir()
+        (package_directory / "__init__.py").write_text("")
+        (package_directory / "good.py").write_text("from . import bad\n")
+        (package_directory / "bad.py").write_bytes(contents)
+
+        class FakePackageFinder(BaseFakePackageFinder):
+            directory_map = {"mypackage": str(package_directory)}
+
+        with override_settings(
+            FILE_SYSTEM=FileSystem(), PACKAGE_FINDER=FakePackageFinder()
+        ):
+            with pytest.raises(exceptions.SourceFileUndecodable):
+                usecases.build_graph("mypackage")
+            graph = usecases.build_graph("mypackage", skip_undecodable_files=True)
+
+        assert {"mypackage", "mypackage.good", "mypackage.bad"} == graph.modules
+        assert graph.direct_import_exists(importer="mypackage.good", imported="mypackage.bad")
+        assert not graph.direct_import_exists(
+            importer="mypackage.bad", imported="mypackage.good"
+        )
+        [skipped_file] = graph.build_info()["skipped_files"]
+        assert str(package_directory / "bad.py") == skipped_file["filename"]
+        assert skipped_file["reason"].startswith(
+            f"Could not decode {package_directory / 'bad.py'}: "
+        )
+
+    @pytest.mark.parametrize("emit_warnings", (True, False))
+    def test_records_warnings(self, emit_warnings):
+        file_system = FakeFileSystem(
+            contents="""
+                /path/to/mypackage/
+                    __init__.py
+                    one.py
+                    one.old.py
+            """,
+            content_map={"/path/to/mypackage/one.py": "from .missing import blue"},
+        )
+
+        class FakePackageFinder(BaseFakePackageFinder):
+            directory_map = {"mypackage": "/path/to/mypackage"}
+
+        with override_settings(
+            FILE_SYSTEM=file_system, PACKAGE_FINDER=FakePackageFinder()
+        ):
+            with warnings.catch_warnings(record=True) as emitted_warnings:
+                warnings.simplefilter("always")
+                graph = usecases.build_graph("mypackage", emit_warnings=emit_warnings)
+
+        expected_warnings = [
+            {
+                "category": "DottedFilenameWarning",
+                "message": "Ignored /path/to/mypackage/one.old.py, as its name contains a dot so "
+                "it can't be imported as a module.",
+            },
+            {
+                "category": "UnresolvedImportWarning",
+                "message": "Could not resolve import of mypackage.missing.blue in mypackage.one, "
+                "line 1.",
+            },
+        ]
+        assert expected_warnings == graph.build_info()["warnings"]
+        assert {
+            "mypackage.one": [{"name": "mypackage.missing.blue", "line_number": 1}]
+        } == graph.build_info()["unresolved_imports"]
+        grimp_warnings = [
+            {"category": warning.category.__name__, "message": str(warning.message)}
+            for warning in emitted_warnings
+            if issubclass(warning.category, exceptions.GrimpWarning)
+        ]
+        assert grimp_warnings == (expected_warnings if emit_warnings else [])
+
+    def test_reports_progress(self):
+        file_system = FakeFileSystem(
+            contents="""
+                /path/to/mypackage/
+                    __init__.py
+                    foo.py
+                /path/to/anotherpackage/
+                    __init__.py
+            """,
+        )
+
+        class FakePackageFinder(BaseFakePackageFinder):
+            directory_map = {
+                "mypackage": "/path/to/mypackage",
+                "anotherpackage": "/path/to/anotherpackage",
+            }
+
+        progress = []
+
+        def record_progress(stage, completed, total):
+            progress.append((stage, completed, total))
+
+        with override_settings(
+            FILE_SYSTEM=file_system, PACKAGE_FINDER=FakePackageFinder()
+        ):
+            usecases.build_graph(
+                "mypackage", "anotherpackage", progress_callback=record_progress
+            )
+
+        assert [
+            ("discovering", 1, 2),
+            ("discovering", 2, 2),
+            ("scanning", 1, 3),
+            ("scanning", 2, 3),
+            ("scanning", 3, 3),
+        ] == progress
+
+    def test_logs_duration_of_each_stage(self):
+        file_system = FakeFileSystem(
+            contents="""
+                /path/to/mypackage/
+                    __init__.py
+                    foo.py
+                    bar.py
+            """,
+            content_map={"/path/to/mypackage/foo.py": "from . import bar"},
+        )
+
+        class FakePackageFinder(BaseFakePackageFinder):
+            directory_map = {"mypackage": "/path/to/mypackage"}
+
+        with override_settings(
+            FILE_SYSTEM=file_system, PACKAGE_FINDER=FakePackageFinder()
+        ), mock.patch.object(usecases, "logger") as logger:
+            usecases.build_graph("mypackage")
+
+        messages = [call[0][0] for call in logger.debug.call_args_list]
+        assert len(messages) == 3
+        assert messages[0].startswith("Found 3 modules in 1 packages in ")
+        assert messages[1].startswith("Scanned 3 modules for imports in ")
+        assert messages[2].startswith("Added 1 imports to the graph in ")
+
+    @pytest.mark.parametrize("include_external_packages", (True, False))
+    def test_records_build_info(self, include_external_packages):
+        file_system = FakeFileSystem(
+            contents="""
+                /path/to/mypackage/
+                    __init__.py
+                /path/to/anotherpackage/
+                    __init__.py
+            """,
+        )
+
+        class FakePackageFinder(BaseFakePackageFinder):
+            directory_map = {
+                "mypackage": "/path/to/mypackage",
+                "anotherpackage": "/path/to/anotherpackage",
+            }
+
+        with override_settings(
+            FILE_SYSTEM=file_system, PACKAGE_FINDER=FakePackageFinder()
+        ):
+            graph = usecases.build_graph(
+                "mypackage",
+                "anotherpackage",
+                include_external_packages=include_external_packages,
+            )
+
+        build_info = graph.build_info()
+        timestamp = build_info.pop("timestamp")
+        assert {
+            "grimp_version": __version__.strip(),
+            "package_names": ["mypackage", "anotherpackage"],
+            "include_external_packages": include_external_packages,
+            "namespace_packages": False,
+            "follow_symlinks": False,
+            "respect_gitignore": False,
+            "include_dynamic_imports": False,
+            "resolve_wildcard_imports": False,
+            "resolve_reexports": False,
+            "include_imported_objects": False,
+            "type_checking_guards": [],
+            "include_cython": False,
+            "max_file_size": None,
+            "skip_undecodable_files": False,
+            "skipped_files": [],
+            "ignored_files": [],
+            "package_directories": {},
+            "external_import_granularity": "root",
+            "generated_file_marker": None,
+            "additional_module_extensions": [],
+            "skipped_directory_names": None,
+            "unresolved_imports": {},
+            "warnings": [],
+        } == build_info
+        # The timestamp should be an ISO 8601 string in UTC.
+        assert timestamp.endswith("+00:00")
+
     def test_boolean_additional_package_raises_type_error(self):
         """
         Tests that a useful error message if build_graph is called
@@ -75,3 +739,185 @@ class TestBuildGraph:
         """
         with pytest.raises(TypeError, match="Package names must be strings, got bool."):
             usecases.build_graph("mypackage", True)
+
+
+class TestBuildGraphFromSources:
+    def test_builds_graph(self):
+        graph = usecases.build_graph_from_sources(
+            {
+                "mypackage": "from . import foo",
+                "mypackage.foo.one": "from .two import blue\nimport decimal",
+                "mypackage.foo.two": "",
+                "mypackage.foo.two.blue": "",
+                "anotherpackage.green": "import mypackage.foo.one",
+            },
+            include_external_packages=True,
+        )
+
+        assert {
+            "mypackage",
+            "mypackage.foo",
+            "mypackage.foo.one",
+            "mypackage.foo.two",
+            "mypackage.foo.two.blue",
+            "anotherpackage",
+            "anotherpackage.green",
+            "decimal",
+        } == graph.modules
+        assert {"mypackage.foo.two.blue", "decimal"} == graph.find_modules_directly_imported_by(
+            "mypackage.foo.one"
+        )
+        assert graph.direct_import_exists(importer="mypackage", imported="mypackage.foo")
+        assert graph.direct_import_exists(
+            importer="anotherpackage.green", imported="mypackage.foo.one"
+        )
+        assert ["anotherpackage", "mypackage"] == graph.build_info()["package_names"]
+
+    def test_passes_options_to_build_graph(self):
+        graph = usecases.build_graph_from_sources(
+            {
+                "mypackage.one": "import importlib\nimportlib.import_module('mypackage.two')",
+                "mypackage.two": "",
+            },
+            include_dynamic_imports=True,
+        )
+
+        assert graph.direct_import_exists(importer="mypackage.one", imported="mypackage.two")
+        assert graph.build_info()["include_dynamic_imports"] is True
+
+    def test_raises_syntax_errors(self):
+        with pytest.raises(exceptions.SourceSyntaxError):
+            usecases.build_graph_from_sources({"mypackage.one": "import"})
+
+
+class TestDiscoverPackages:
+    def _create_project(self, tmp_path, config_files, package_directories):
+        for filename, contents in config_files.items():
+            (tmp_path / filename).write_text(contents)
+        for package_directory in package_directories:
+            (tmp_path / package_directory).mkdir(parents=True)
+            (tmp_path / package_directory / "__init__.py").write_text("")
+
+    def _discover_packages(self, tmp_path):
+        with override_settings(FILE_SYSTEM=FileSystem()):
+            return usecases.discover_packages(str(tmp_path))
+
+    def test_setup_cfg_find_packages_in_where_directory(self, tmp_path):
+        self._create_project(
+            tmp_path,
+            config_files={
+                "setup.cfg": (
+                    "[options]\n"
+                    "package_dir =\n"
+                    "    =lib\n"
+                    "packages = find:\n"
+                    "\n"
+                    "[options.packages.find]\n"
+                    "where = lib\n"
+                    "exclude =\n"
+                    "    tests*\n"
+                )
+            },
+            package_directories=[
+                "lib/mypackage",
+                "lib/mypackage/subpackage",
+                "lib/anotherpackage",
+                "lib/tests",
+            ],
+        )
+        # Directories without an __init__.py are not packages.
+        (tmp_path / "lib" / "notapackage").mkdir()
+
+        assert {
+            "mypackage": str(tmp_path / "lib" / "mypackage"),
+            "anotherpackage": str(tmp_path / "lib" / "anotherpackage"),
+        } == self._discover_packages(tmp_path)
+
+    def test_setup_cfg_listed_packages(self, tmp_path):
+        self._create_project(
+            tmp_path,
+            config_files={
+                "setup.cfg": (
+                    "[options]\n"
+                    "packages =\n"
+                    "    mypackage\n"
+                    "    mypackage.subpackage\n"
+                    "package_dir =\n"
+                    "    mypackage = source/mypackage\n"
+                )
+            },
+            package_directories=["source/mypackage", "source/mypackage/subpackage"],
+        )
+
+        assert {"mypackage": str(tmp_path / "source" / "mypackage")} == self._discover_packages(
+            tmp_path
+        )
+
+    def test_setup_cfg_package_in_directory_with_different_name(self, tmp_path):
+        self._create_project(
+            tmp_path,
+            config_files={
+                "setup.cfg": (
+                    "[options]\n"
+                    "packages = mypackage\n"
+                    "package_dir =\n"
+                    "    mypackage = lib\n"
+                )
+            },
+            package_directories=["lib"],
+        )
+        (tmp_path / "lib" / "one.py").write_text("from mypackage import two\n")
+        (tmp_path / "lib" / "two.py").write_text("")
+
+        packages = self._discover_packages(tmp_path)
+        with override_settings(FILE_SYSTEM=FileSystem()):
+            graph = usecases.build_graph(*packages, package_directories=packages)
+
+        assert {"mypackage": str(tmp_path / "lib")} == packages
+        assert {"mypackage", "mypackage.one", "mypackage.two"} == graph.modules
+        assert graph.direct_import_exists(importer="mypackage.one", imported="mypackage.two")
+
+    @pytest.mark.skipif(
+        not projectconfig.CAN_READ_PYPROJECT_TOML, reason="No TOML parser is installed."
+    )
+    def test_pyproject_toml(self, tmp_path):
+        self._create_project(
+            tmp_path,
+            config_files={
+                "pyproject.toml": (
+                    '[project]\nname = "my-distribution"\n\n'
+                    '[tool.setuptools.packages.find]\nwhere = ["lib"]\nexclude = ["tests"]\n'
+                ),
+                # Ignored, as pyproject.toml takes precedence.
+                "setup.cfg": "[options]\npackages = anotherpackage\n",
+            },
+            package_directories=["lib/mypackage", "lib/tests"],
+        )
+
+        assert {"mypackage": str(tmp_path / "lib" / "mypackage")} == self._discover_packages(
+            tmp_path
+        )
+
+    @pytest.mark.parametrize(
+        "package_directories, expected_directory",
+        (
+            (["src/mypackage", "tests"], "src/mypackage"),
+            (["mypackage", "tests", "docs"], "mypackage"),
+        ),
+    )
+    def test_discovers_packages_automatically_if_not_configured(
+        self, tmp_path, package_directories, expected_directory
+    ):
+        self._create_project(
+            tmp_path,
+            config_files={"setup.cfg": "[metadata]\nname = mypackage\n"},
+            package_directories=package_directories,
+        )
+
+        assert {"mypackage": str(tmp_path / expected_directory)} == self._discover_packages(
+            tmp_path
+        )
+
+    def test_raises_value_error_if_no_config_file(self, tmp_path):
+        with pytest.raises(ValueError):
+            self._discover_packages(tmp_path)
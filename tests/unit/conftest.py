@@ -4,6 +4,7 @@ from grimp.application.config import settings
 from grimp.adaptors.graph import ImportGraph
 from grimp.adaptors.modulefinder import ModuleFinder
 from grimp.adaptors.importscanner import ImportScanner
+from grimp.adaptors.memoryfilesystem import InMemoryFileSystem
 
 
 @pytest.fixture(scope="module", autouse=True)
@@ -13,4 +14,5 @@ def configure_unit_tests():
         MODULE_FINDER=ModuleFinder(),
         IMPORT_SCANNER_CLASS=ImportScanner,
         FILE_SYSTEM=None,
+        IN_MEMORY_FILE_SYSTEM_CLASS=InMemoryFileSystem,
     )
@@ -0,0 +1,39 @@
+import sys
+
+import pytest  # type: ignore
+
+from grimp.domain import stdlib
+
+
+@pytest.mark.parametrize(
+    "module_name, expected_result",
+    (
+        ("os", True),
+        ("os.path", True),
+        ("collections.abc", True),
+        ("__future__", True),
+        ("_thread", True),
+        ("django", False),
+        ("django.db", False),
+        ("oss", False),
+    ),
+)
+def test_is_stdlib_module(module_name, expected_result):
+    assert expected_result == stdlib.is_stdlib_module(module_name)
+
+
+def test_bundled_names_include_common_modules():
+    for module_name in ("abc", "asyncio", "decimal", "json", "sys", "typing", "xml"):
+        assert module_name in stdlib.BUNDLED_STDLIB_MODULE_NAMES
+
+
+@pytest.mark.skipif(
+    not hasattr(sys, "stdlib_module_names"),
+    reason="sys.stdlib_module_names was added in Python 3.10.",
+)
+def test_uses_running_python_stdlib_module_names_where_available():
+    assert frozenset(sys.stdlib_module_names) == stdlib.get_stdlib_module_names()
+
+
+def test_stdlib_module_names_are_only_computed_once():
+    assert stdlib.get_stdlib_module_names() is stdlib.get_stdlib_module_names()
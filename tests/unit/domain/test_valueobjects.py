@@ -121,9 +121,89 @@ class TestDirectImport:
             line_number=10,
             line_contents="from . import bar",
         )
+        g = DirectImport(
+            importer=Module("foo"),
+            imported=Module("bar"),
+            line_number=10,
+            line_contents="import bar",
+            is_dynamic=True,
+        )
+        h = DirectImport(
+            importer=Module("foo"),
+            imported=Module("bar"),
+            line_number=10,
+            line_contents="import bar",
+            is_type_checking=True,
+        )
+        i = DirectImport(
+            importer=Module("foo"),
+            imported=Module("bar"),
+            line_number=10,
+            line_contents="import bar",
+            ignored_for=("my-contract",),
+        )
+        j = DirectImport(
+            importer=Module("foo"),
+            imported=Module("bar"),
+            line_number=10,
+            line_contents="import bar",
+            is_cimport=True,
+        )
+        k = DirectImport(
+            importer=Module("foo"),
+            imported=Module("bar"),
+            line_number=10,
+            line_contents="import bar",
+            column=4,
+        )
+        m = DirectImport(
+            importer=Module("foo"),
+            imported=Module("bar"),
+            line_number=10,
+            line_contents="import bar",
+            end_line_number=12,
+        )
+        n = DirectImport(
+            importer=Module("foo"),
+            imported=Module("bar"),
+            line_number=10,
+            line_contents="import bar",
+            platform_condition='sys.platform == "win32"',
+        )
+        o = DirectImport(
+            importer=Module("foo"),
+            imported=Module("bar"),
+            line_number=10,
+            line_contents="import bar",
+            is_optional=True,
+        )
+        p = DirectImport(
+            importer=Module("foo"),
+            imported=Module("bar"),
+            line_number=10,
+            line_contents="import bar",
+            is_lazy=True,
+        )
+        q = DirectImport(
+            importer=Module("foo"),
+            imported=Module("bar"),
+            line_number=10,
+            line_contents="import bar",
+            version_condition="sys.version_info >= (3, 8)",
+        )
 
         assert hash(a) == hash(b)
         assert hash(a) != hash(c)
         assert hash(a) != hash(d)
         assert hash(a) != hash(e)
         assert hash(a) != hash(f)
+        assert hash(a) != hash(g)
+        assert hash(a) != hash(h)
+        assert hash(a) != hash(i)
+        assert hash(a) != hash(j)
+        assert hash(a) == hash(k)
+        assert hash(a) == hash(m)
+        assert hash(a) != hash(n)
+        assert hash(a) != hash(o)
+        assert hash(a) != hash(p)
+        assert hash(a) != hash(q)
@@ -36,3 +36,82 @@ class TestSourceSyntaxError:
         ) != exceptions.SourceSyntaxError(
             filename="path/to/somefile.py", lineno=3, text="something else wrong",
         )
+
+
+class TestDuplicateModuleFound:
+    def test_str(self):
+        assert (
+            "Module mypackage.foo was found in more than one place: "
+            "/path/to/one/mypackage/foo.py, /path/to/two/mypackage/foo/__init__.py"
+        ) == str(
+            exceptions.DuplicateModuleFound(
+                module="mypackage.foo",
+                filenames=[
+                    "/path/to/one/mypackage/foo.py",
+                    "/path/to/two/mypackage/foo/__init__.py",
+                ],
+            )
+        )
+
+    def test_same_values_are_equal(self):
+        assert exceptions.DuplicateModuleFound(
+            module="mypackage.foo", filenames=["/one/mypackage/foo.py", "/two/mypackage/foo.py"]
+        ) == exceptions.DuplicateModuleFound(
+            module="mypackage.foo", filenames=["/one/mypackage/foo.py", "/two/mypackage/foo.py"]
+        )
+
+    def test_different_modules_are_not_equal(self):
+        assert exceptions.DuplicateModuleFound(
+            module="mypackage.foo", filenames=["/one/mypackage/foo.py", "/two/mypackage/foo.py"]
+        ) != exceptions.DuplicateModuleFound(
+            module="mypackage.bar", filenames=["/one/mypackage/foo.py", "/two/mypackage/foo.py"]
+        )
+
+
+class TestSourceFileTooLarge:
+    def test_str(self):
+        assert (
+            "path/to/somefile.py is 2000 bytes, which is larger than the maximum of 1000 bytes."
+            == str(
+                exceptions.SourceFileTooLarge(
+                    filename="path/to/somefile.py", size=2000, max_file_size=1000
+                )
+            )
+        )
+
+    def test_same_values_are_equal(self):
+        assert exceptions.SourceFileTooLarge(
+            filename="path/to/somefile.py", size=2000, max_file_size=1000
+        ) == exceptions.SourceFileTooLarge(
+            filename="path/to/somefile.py", size=2000, max_file_size=1000
+        )
+
+    def test_different_sizes_are_not_equal(self):
+        assert exceptions.SourceFileTooLarge(
+            filename="path/to/somefile.py", size=2000, max_file_size=1000
+        ) != exceptions.SourceFileTooLarge(
+            filename="path/to/somefile.py", size=3000, max_file_size=1000
+        )
+
+
+class TestSourceFileUndecodable:
+    def test_str(self):
+        assert "Could not decode path/to/somefile.py: unknown encoding: bogus" == str(
+            exceptions.SourceFileUndecodable(
+                filename="path/to/somefile.py", reason="unknown encoding: bogus"
+            )
+        )
+
+    def test_same_values_are_equal(self):
+        assert exceptions.SourceFileUndecodable(
+            filename="path/to/somefile.py", reason="unknown encoding: bogus"
+        ) == exceptions.SourceFileUndecodable(
+            filename="path/to/somefile.py", reason="unknown encoding: bogus"
+        )
+
+    def test_different_filenames_are_not_equal(self):
+        assert exceptions.SourceFileUndecodable(
+            filename="path/to/somefile.py", reason="unknown encoding: bogus"
+        ) != exceptions.SourceFileUndecodable(
+            filename="path/to/anotherfile.py", reason="unknown encoding: bogus"
+        )